@@ -0,0 +1,58 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Collects expected puzzle answers from the `answers` directory
+///
+/// Every `answers/day_XX.txt` file contains lines like `1: 12345` where
+/// the number before the colon is the puzzle part.
+fn main() {
+    let answers_dir = Path::new("answers");
+    // A missing path would make cargo rerun the script on every build. Without
+    // any directive cargo reruns it on changes in the package, which notices
+    // the directory once it is created
+    if answers_dir.is_dir() {
+        println!("cargo:rerun-if-changed={}", answers_dir.display());
+    }
+
+    let mut answers = vec!();
+    if let Ok(entries) = fs::read_dir(answers_dir) {
+        for entry in entries {
+            let path = entry.expect("Cannot read answers directory").path();
+            println!("cargo:rerun-if-changed={}", path.display());
+            let day = path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_prefix("day_"))
+                .and_then(|n| n.strip_suffix(".txt"))
+                .and_then(|n| n.parse::<u32>().ok());
+            let Some(day) = day else {
+                continue;
+            };
+
+            let content = fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("Cannot read {}: {e}", path.display()));
+            for line in content.lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let (part, answer) = line.split_once(':')
+                    .unwrap_or_else(|| panic!("Expect 'PART: ANSWER' in {}: {line}", path.display()));
+                let part = part.trim().parse::<u32>()
+                    .unwrap_or_else(|_| panic!("Part must be an integer in {}: {line}", path.display()));
+                answers.push((day, part, answer.trim().to_string()));
+            }
+        }
+    }
+    answers.sort();
+
+    let mut code = String::from("&[\n");
+    for (day, part, answer) in answers {
+        code.push_str(&format!("    ({day}, {part}, {answer:?}),\n"));
+    }
+    code.push_str("]\n");
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set");
+    fs::write(Path::new(&out_dir).join("answers.rs"), code)
+        .expect("Cannot write expected answers");
+}
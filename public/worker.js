@@ -0,0 +1,16 @@
+// Web worker that runs puzzle solvers off the main thread.
+// The application module is built by the Dioxus CLI into the assets directory.
+import init, { solve_puzzles } from "./assets/dioxus/advent-2023.js";
+
+const initialized = init();
+
+self.onmessage = async (event) => {
+    await initialized;
+    try {
+        self.postMessage(solve_puzzles(event.data));
+    } catch (e) {
+        // A panic is reported by the panic hook before this message,
+        // the page ignores everything after the first fatal error
+        self.postMessage(JSON.stringify({ error: `Solver crashed: ${e}`, fatal: true }));
+    }
+};
@@ -0,0 +1,115 @@
+use std::fmt;
+
+use num::BigInt;
+
+use crate::registry::{self, PuzzleId};
+use crate::solution::Value;
+
+/// Expected answers collected from the `answers/day_XX.txt` files by the build script
+const EXPECTED_ANSWERS: &[(u32, u32, &str)] = include!(concat!(env!("OUT_DIR"), "/answers.rs"));
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Incorrect { expected: Value },
+    Unknown,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::Incorrect { expected } => write!(f, "incorrect, expected {expected}"),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+pub fn expected_answer(puzzle_id: PuzzleId) -> Option<Value> {
+    find_expected_answer(EXPECTED_ANSWERS, puzzle_id)
+}
+
+pub fn verify(puzzle_id: PuzzleId, value: &Value) -> Verdict {
+    compare(expected_answer(puzzle_id), value)
+}
+
+/// Same as [verify] but an example input is checked against the answer of the example
+pub fn verify_input(puzzle_id: PuzzleId, input: &str, value: &Value) -> Verdict {
+    let example = registry::find_day(puzzle_id.day)
+        .and_then(|day| day.find_example(input));
+    match example {
+        Some(example) => compare(example.answer(puzzle_id.part), value),
+        None => verify(puzzle_id, value),
+    }
+}
+
+fn compare(expected: Option<Value>, value: &Value) -> Verdict {
+    match expected {
+        Some(expected) if &expected == value => Verdict::Correct,
+        Some(expected) => Verdict::Incorrect { expected },
+        None => Verdict::Unknown,
+    }
+}
+
+fn find_expected_answer(
+    answers: &[(u32, u32, &str)],
+    puzzle_id: PuzzleId,
+) -> Option<Value> {
+    answers.iter()
+        .find(|(day, part, _)| *day == puzzle_id.day && *part == puzzle_id.part.ord())
+        .map(|(_, _, answer)| parse_value(answer))
+}
+
+fn parse_value(s: &str) -> Value {
+    if let Ok(v) = s.parse::<i64>() {
+        Value::Int(v)
+    } else if let Ok(v) = s.parse::<BigInt>() {
+        Value::BigInt(v)
+    } else {
+        Value::Str(s.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry::Part;
+    use super::*;
+
+    #[test]
+    fn test_find_expected_answer() {
+        let answers = &[(5, 1, "35"), (5, 2, "46"), (13, 1, "HELLO")];
+        assert_eq!(
+            find_expected_answer(answers, PuzzleId::new(5, Part::Two)),
+            Some(46.into())
+        );
+        assert_eq!(
+            find_expected_answer(answers, PuzzleId::new(13, Part::One)),
+            Some("HELLO".into())
+        );
+        assert_eq!(
+            find_expected_answer(answers, PuzzleId::new(13, Part::Two)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_verify_input() {
+        let puzzle_id = PuzzleId::new(6, Part::One);
+        let example = registry::find_day(6).unwrap().examples[0];
+        assert_eq!(verify_input(puzzle_id, example.input, &288.into()), Verdict::Correct);
+        assert_eq!(
+            verify_input(puzzle_id, example.input, &1.into()),
+            Verdict::Incorrect { expected: 288.into() }
+        );
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("-12"), Value::Int(-12));
+        assert_eq!(
+            parse_value("18446744073709551615"),
+            Value::BigInt(BigInt::from(u64::MAX))
+        );
+        assert_eq!(parse_value("ABC"), Value::Str("ABC".to_string()));
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use crate::html::escape_html;
+use crate::input_cache::InputCache;
+use crate::registry::{self, Day, Part, PuzzleId};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timings {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Timings {
+    fn from_durations(durations: &mut [Duration]) -> Option<Self> {
+        durations.sort();
+        Some(Self {
+            min: *durations.first()?,
+            median: durations[durations.len() / 2],
+            max: *durations.last()?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Measurement {
+    Timings(Timings),
+    MissingInput,
+    Error(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BenchResult {
+    pub puzzle_id: PuzzleId,
+    pub measurement: Measurement,
+}
+
+/// Runs every registered solver `iterations` times on the cached user inputs
+pub fn run(cache: &InputCache, iterations: usize) -> anyhow::Result<Vec<BenchResult>> {
+    if iterations == 0 {
+        anyhow::bail!("Number of iterations must be positive");
+    }
+
+    let mut results = vec!();
+    for day in registry::days() {
+        let input = cache.read(day.ord)?;
+        for part in Part::ALL {
+            let measurement = if let Some(input) = &input {
+                measure(day, part, input, iterations)
+            } else {
+                Measurement::MissingInput
+            };
+            results.push(BenchResult { puzzle_id: day.puzzle_id(part), measurement });
+        }
+    }
+    Ok(results)
+}
+
+fn measure(day: &Day, part: Part, input: &str, iterations: usize) -> Measurement {
+    let mut durations = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let res = day.solve(part, input);
+        durations.push(start.elapsed());
+        if let Err(e) = res {
+            return Measurement::Error(e.to_string());
+        }
+    }
+    match Timings::from_durations(&mut durations) {
+        Some(timings) => Measurement::Timings(timings),
+        None => Measurement::Error("No measurements".to_string()),
+    }
+}
+
+pub fn format_table(results: &[BenchResult]) -> String {
+    let mut table = String::new();
+    writeln!(table, "{:<7}{:>14}{:>14}{:>14}", "Puzzle", "Min", "Median", "Max").ok();
+    for res in results {
+        match &res.measurement {
+            Measurement::Timings(t) => {
+                writeln!(
+                    table,
+                    "{:<7}{:>14}{:>14}{:>14}",
+                    res.puzzle_id.to_string(),
+                    format!("{:?}", t.min),
+                    format!("{:?}", t.median),
+                    format!("{:?}", t.max),
+                ).ok();
+            }
+            Measurement::MissingInput => {
+                writeln!(table, "{:<7}{:>14}", res.puzzle_id.to_string(), "no input").ok();
+            }
+            Measurement::Error(e) => {
+                writeln!(table, "{:<7}  error: {e}", res.puzzle_id.to_string()).ok();
+            }
+        }
+    }
+    table
+}
+
+pub fn format_html(results: &[BenchResult]) -> String {
+    let mut rows = String::new();
+    for res in results {
+        let cells = match &res.measurement {
+            Measurement::Timings(t) => {
+                format!("<td>{:?}</td><td>{:?}</td><td>{:?}</td>", t.min, t.median, t.max)
+            }
+            Measurement::MissingInput => {
+                "<td colspan=\"3\">no input</td>".to_string()
+            }
+            Measurement::Error(e) => {
+                format!("<td colspan=\"3\">error: {}</td>", escape_html(e))
+            }
+        };
+        writeln!(rows, "      <tr><td>{}</td>{cells}</tr>", res.puzzle_id).ok();
+    }
+
+    format!(r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <title>Advent of Code 2023 Benchmark</title>
+    <style>
+      table {{ border-collapse: collapse; font-family: monospace; }}
+      th, td {{ border: 1px solid #ccc; padding: 2px 8px; text-align: right; }}
+    </style>
+  </head>
+  <body>
+    <table>
+      <tr><th>Puzzle</th><th>Min</th><th>Median</th><th>Max</th></tr>
+{rows}    </table>
+  </body>
+</html>
+"#)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::TempDir;
+    use super::*;
+
+    #[test]
+    fn test_timings() {
+        let mut durations = [5, 1, 3, 2, 100]
+            .map(Duration::from_millis);
+        assert_eq!(
+            Timings::from_durations(&mut durations),
+            Some(Timings {
+                min: Duration::from_millis(1),
+                median: Duration::from_millis(3),
+                max: Duration::from_millis(100),
+            })
+        );
+        assert_eq!(Timings::from_durations(&mut []), None);
+    }
+
+    #[test]
+    fn test_run() -> anyhow::Result<()> {
+        let dir = TempDir::new("bench");
+        let cache = InputCache::new(dir.path());
+        cache.write(6, "Time:      7  15   30\nDistance:  9  40  200\n")?;
+        cache.write(7, "32T3 765\n")?;
+
+        let results = run(&cache, 3)?;
+
+        assert_eq!(results.len(), registry::days().len() * 2);
+        for res in &results {
+            match res.puzzle_id.day {
+                6 => assert!(matches!(res.measurement, Measurement::Timings(_))),
+                7 => assert!(matches!(res.measurement, Measurement::Error(_))),
+                _ => assert_eq!(res.measurement, Measurement::MissingInput),
+            }
+        }
+
+        let table = format_table(&results);
+        assert!(table.contains("06-1"));
+        assert!(table.contains("no input"));
+        let html = format_html(&results);
+        assert!(html.contains("<td>06-2</td>"));
+        Ok(())
+    }
+}
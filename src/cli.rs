@@ -0,0 +1,197 @@
+use std::io::{IsTerminal, Read};
+use std::time::Instant;
+
+use anyhow::Context;
+
+use crate::answers;
+use crate::bench;
+use crate::input_cache::InputCache;
+use crate::registry::{self, Day, Part};
+
+const USAGE: &str = "\
+Usage:
+    advent-2023 solve <DAY> <PART> [INPUT_FILE]
+    advent-2023 cache <DAY> [INPUT_FILE]
+    advent-2023 bench [ITERATIONS] [--html FILE]
+    advent-2023 list
+
+When INPUT_FILE is omitted the input is read from stdin if it is piped,
+otherwise the cached input of the day is used if it exists. Use '-' to force
+reading from stdin.
+The cache command saves an input into the local input cache.
+The bench command runs every solver on the cached inputs and reports timings.";
+
+const DEFAULT_BENCH_ITERATIONS: usize = 10;
+
+pub fn run(args: impl IntoIterator<Item = String>) -> anyhow::Result<()> {
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        Some("solve") => {
+            let day = args.next().context("Expect day")?;
+            let part = args.next().context("Expect part")?;
+            let input_file = args.next();
+            solve(&day, &part, input_file.as_deref())
+        }
+        Some("cache") => {
+            let day = args.next().context("Expect day")?;
+            let input_file = args.next();
+            cache(&day, input_file.as_deref())
+        }
+        Some("bench") => {
+            let mut iterations = DEFAULT_BENCH_ITERATIONS;
+            let mut html_file = None;
+            while let Some(arg) = args.next() {
+                if arg == "--html" {
+                    html_file = Some(args.next().context("Expect html file")?);
+                } else {
+                    iterations = arg.parse()
+                        .with_context(|| format!("Iterations must be an integer: {arg}"))?;
+                }
+            }
+            run_bench(iterations, html_file.as_deref())
+        }
+        Some("list") => {
+            for day_ord in 1..=25 {
+                println!("{}", format_day_status(day_ord, registry::find_day(day_ord)));
+            }
+            Ok(())
+        }
+        Some("help" | "-h" | "--help") => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        Some(cmd) => anyhow::bail!("Unknown command: {cmd}\n\n{USAGE}"),
+        None => anyhow::bail!("Missing command\n\n{USAGE}"),
+    }
+}
+
+fn solve(day: &str, part: &str, input_file: Option<&str>) -> anyhow::Result<()> {
+    let day_ord = parse_day(day)?;
+    let day = registry::find_day(day_ord)
+        .with_context(|| format!("Day {day_ord} is not solved yet"))?;
+    let part = part.parse::<Part>()?;
+    let input = read_input(day.ord, input_file)?;
+
+    let start = Instant::now();
+    let res = day.solve(part, &input)?;
+    let elapsed = start.elapsed();
+
+    let verdict = answers::verify_input(day.puzzle_id(part), &input, &res.value);
+    println!("Day {:0>2}, part {part}: {} ({verdict})", day.ord, res.value);
+    for diagnostic in &res.diagnostics {
+        println!("{}: {}", diagnostic.name, diagnostic.value);
+    }
+    if let Some(visualization) = &res.visualization {
+        println!("{visualization}");
+    }
+    println!("Elapsed: {elapsed:?}");
+    Ok(())
+}
+
+fn cache(day: &str, input_file: Option<&str>) -> anyhow::Result<()> {
+    let day_ord = parse_day(day)?;
+    let input = match input_file {
+        None | Some("-") => read_stdin()?,
+        Some(input_file) => read_file(input_file)?,
+    };
+    if input.is_empty() {
+        anyhow::bail!("Empty input");
+    }
+
+    let cache = InputCache::from_env();
+    cache.write(day_ord, &input)?;
+    println!("Saved input to {}", cache.input_path(day_ord).display());
+    Ok(())
+}
+
+fn run_bench(iterations: usize, html_file: Option<&str>) -> anyhow::Result<()> {
+    let results = bench::run(&InputCache::from_env(), iterations)?;
+    print!("{}", bench::format_table(&results));
+    if let Some(html_file) = html_file {
+        std::fs::write(html_file, bench::format_html(&results))
+            .with_context(|| format!("Cannot write benchmark report: {html_file}"))?;
+    }
+    Ok(())
+}
+
+/// Line of the `list` command, days that are not registered yet are marked with `-`
+fn format_day_status(day_ord: u32, day: Option<&Day>) -> String {
+    match day {
+        Some(day) => format!("{day_ord:0>2} {}", day.status),
+        None => format!("{day_ord:0>2} -"),
+    }
+}
+
+fn parse_day(day: &str) -> anyhow::Result<u32> {
+    day.parse()
+        .with_context(|| format!("Day must be an integer: {day}"))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum InputSource<'a> {
+    File(&'a str),
+    Stdin,
+    /// Falls back to stdin when the day has no cached input
+    Cache,
+}
+
+/// Piped input wins over the cache, which only saves typing at the terminal
+fn input_source(input_file: Option<&str>, stdin_is_terminal: bool) -> InputSource<'_> {
+    match input_file {
+        Some("-") => InputSource::Stdin,
+        Some(input_file) => InputSource::File(input_file),
+        None if stdin_is_terminal => InputSource::Cache,
+        None => InputSource::Stdin,
+    }
+}
+
+fn read_input(day_ord: u32, input_file: Option<&str>) -> anyhow::Result<String> {
+    match input_source(input_file, std::io::stdin().is_terminal()) {
+        InputSource::File(input_file) => read_file(input_file),
+        InputSource::Stdin => read_stdin(),
+        InputSource::Cache => {
+            if let Some(input) = InputCache::from_env().read(day_ord)? {
+                Ok(input)
+            } else {
+                read_stdin()
+            }
+        }
+    }
+}
+
+fn read_file(input_file: &str) -> anyhow::Result<String> {
+    std::fs::read_to_string(input_file)
+        .with_context(|| format!("Cannot read input file: {input_file}"))
+}
+
+fn read_stdin() -> anyhow::Result<String> {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)
+        .context("Cannot read input from stdin")?;
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry::{Status, Solver};
+    use super::*;
+
+    #[test]
+    fn test_format_day_status() {
+        let solver: Solver = |_| anyhow::bail!("Not solved");
+        let day = Day::new(18, "", solver, solver, &[]);
+        assert_eq!(format_day_status(18, Some(&day)), "18 solved");
+        let day = day.with_status(Status::Unfinished);
+        assert_eq!(format_day_status(18, Some(&day)), "18 unfinished");
+        assert_eq!(format_day_status(19, None), "19 -");
+    }
+
+    #[test]
+    fn test_input_source() {
+        assert_eq!(input_source(Some("input.txt"), false), InputSource::File("input.txt"));
+        assert_eq!(input_source(Some("input.txt"), true), InputSource::File("input.txt"));
+        assert_eq!(input_source(Some("-"), true), InputSource::Stdin);
+        assert_eq!(input_source(None, false), InputSource::Stdin);
+        assert_eq!(input_source(None, true), InputSource::Cache);
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Steps are counted from the initial state which is step 0
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// First step of the cycle
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    /// Maps any step to the step with the same state that was seen before the repetition
+    pub fn reduce(&self, step: usize) -> usize {
+        if step < self.offset {
+            step
+        } else {
+            self.offset + (step - self.offset) % self.len
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct History<S> {
+    /// States from the initial one up to the repetition exclusive
+    pub states: Vec<S>,
+    /// `None` when the states did not repeat within the limit
+    pub cycle: Option<Cycle>,
+}
+
+/// Runs the state machine until a state repeats or `max_steps` steps are made.
+/// States are compared by the key so it can be a hash of a big state
+pub fn detect<S, K: Hash + Eq>(
+    initial: S,
+    max_steps: usize,
+    mut key: impl FnMut(&S) -> K,
+    mut step: impl FnMut(&S) -> S,
+) -> History<S> {
+    let mut seen = HashMap::new();
+    let mut states = vec!(initial);
+    loop {
+        let cur_step = states.len() - 1;
+        let cur = &states[cur_step];
+        let cur_key = key(cur);
+        if let Some(&offset) = seen.get(&cur_key) {
+            states.pop();
+            let cycle = Cycle { offset, len: cur_step - offset };
+            return History { states, cycle: Some(cycle) };
+        }
+        if cur_step == max_steps {
+            return History { states, cycle: None };
+        }
+        seen.insert(cur_key, cur_step);
+        let next = step(cur);
+        states.push(next);
+    }
+}
+
+/// Returns the state after `num_steps` steps jumping over repeated cycles
+pub fn run<S, K: Hash + Eq>(
+    initial: S,
+    num_steps: usize,
+    key: impl FnMut(&S) -> K,
+    step: impl FnMut(&S) -> S,
+) -> (S, Option<Cycle>) {
+    let mut history = detect(initial, num_steps, key, step);
+    let state_ix = history.cycle.map_or(num_steps, |cycle| cycle.reduce(num_steps));
+    (history.states.swap_remove(state_ix), history.cycle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn next(x: &u32) -> u32 {
+        (x * x + 1) % 101
+    }
+
+    fn brute_force(initial: u32, num_steps: usize) -> u32 {
+        (0..num_steps).fold(initial, |x, _| next(&x))
+    }
+
+    #[test]
+    fn test_detect() {
+        // 0, 1, 2, 5, 26, 71, 93, 65, 85, 55, 97, 17, 88, 69, 15, 24, 72, 34, 46, 97
+        let history = detect(0, 1000, |&x| x, next);
+        assert_eq!(history.cycle, Some(Cycle { offset: 10, len: 9 }));
+        assert_eq!(history.states.len(), 19);
+        assert_eq!(history.states[10], 97);
+        assert_eq!(history.states[18], 46);
+
+        let history = detect(0, 5, |&x| x, next);
+        assert_eq!(history.cycle, None);
+        assert_eq!(history.states, vec!(0, 1, 2, 5, 26, 71));
+    }
+
+    #[test]
+    fn test_run() {
+        for num_steps in [0, 1, 2, 9, 10, 11, 18, 19, 20, 28, 100, 1000] {
+            assert_eq!(run(0, num_steps, |&x| x, next).0, brute_force(0, num_steps), "{num_steps}");
+        }
+        assert_eq!(run(0, 1_000_000_000, |&x| x, next), (97, Some(Cycle { offset: 10, len: 9 })));
+    }
+
+    #[test]
+    fn test_run_with_key() {
+        // The key only looks at the parity so the cycle is found earlier
+        // than the one of the values
+        let (state, cycle) = run(1u32, 10, |&x| x % 2, |&x| x + 2);
+        assert_eq!(cycle, Some(Cycle { offset: 0, len: 1 }));
+        assert_eq!(state, 1);
+    }
+
+    #[test]
+    fn test_cycle_reduce() {
+        let cycle = Cycle { offset: 2, len: 3 };
+        assert_eq!(
+            (0..10).map(|step| cycle.reduce(step)).collect::<Vec<_>>(),
+            vec!(0, 1, 2, 3, 4, 2, 3, 4, 2, 3)
+        );
+    }
+}
@@ -1,3 +1,7 @@
+use indoc::indoc;
+
+use crate::registry::Example;
+
 pub fn solve_1(input: &str) -> crate::PuzzleResult {
     let mut res = 0;
     for row in input.lines() {
@@ -14,7 +18,7 @@ pub fn solve_1(input: &str) -> crate::PuzzleResult {
             res += n1 * 10 + n2;
         }
     }
-    Ok(res.to_string())
+    Ok(res.into())
 }
 
 pub fn solve_2(input: &str) -> crate::PuzzleResult {
@@ -54,35 +58,39 @@ pub fn solve_2(input: &str) -> crate::PuzzleResult {
             res += n1 * 10 + n2;
         }
     }
-    Ok(res.to_string())
+    Ok(res.into())
 }
 
+const EXAMPLE_INPUT_1: &str = indoc!{"
+    1abc2
+    pqr3stu8vwx
+    a1b2c3d4e5f
+    treb7uchet
+"};
+const EXAMPLE_INPUT_2: &str = indoc!{"
+    two1nine
+    eightwothree
+    abcone2threexyz
+    xtwone3four
+    4nineeightseven2
+    zoneight234
+    7pqrstsixteen
+"};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT_1, Some(142), None),
+    Example::new(EXAMPLE_INPUT_2, None, Some(281)),
+];
+
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use super::*;
 
-    const EXAMPLE_INPUT_1: &'static str = indoc!{"
-        1abc2
-        pqr3stu8vwx
-        a1b2c3d4e5f
-        treb7uchet
-    "};
-    const EXAMPLE_INPUT_2: &'static str = indoc!{"
-        two1nine
-        eightwothree
-        abcone2threexyz
-        xtwone3four
-        4nineeightseven2
-        zoneight234
-        7pqrstsixteen
-    "};
-
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT_1)?,
-            "142"
+            solve_1(EXAMPLE_INPUT_1)?.value,
+            142.into()
         );
         Ok(())
     }
@@ -90,8 +98,8 @@ mod tests {
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_2(EXAMPLE_INPUT_2)?,
-            "281"
+            solve_2(EXAMPLE_INPUT_2)?.value,
+            281.into()
         );
         Ok(())
     }
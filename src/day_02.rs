@@ -1,6 +1,7 @@
-use std::str::FromStr;
+use indoc::indoc;
 
-use anyhow::Context;
+use crate::parse_error::{self, Line, ParseError};
+use crate::registry::Example;
 
 const RED_CUBES: u32 = 12;
 const GREEN_CUBES: u32 = 13;
@@ -12,26 +13,19 @@ struct Game {
     pub outcomes: Vec<Outcome>,
 }
 
-impl FromStr for Game {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some(s) = s.strip_prefix("Game ") {
-            if let Some((game_num_str, outecomes_str)) = s.split_once(':') {
-                let id = game_num_str.parse()
-                    .context("Game identifier must be an integer")?;
-                let mut outcomes = vec!();
-                for outcome_str in outecomes_str.split(';') {
-                    let outcome_str = outcome_str.trim();
-                    outcomes.push(outcome_str.parse()?);
-                }
-                Ok(Self { id, outcomes })
-            } else {
-                anyhow::bail!("':' is expected before game outcomes")
-            }
-        } else {
-            anyhow::bail!("String must start with 'Game ' prefix")
+impl Game {
+    fn parse(line: &Line, s: &str) -> Result<Self, ParseError> {
+        let (game_num_str, outcomes_str) = s.strip_prefix("Game ")
+            .and_then(|s| s.split_once(':'))
+            .ok_or_else(|| line.error(s, "Expect 'Game <id>:' before game outcomes"))?;
+        let id = game_num_str.parse()
+            .map_err(|_| line.error(game_num_str, "Game identifier must be an integer"))?;
+        let mut outcomes = vec!();
+        for outcome_str in outcomes_str.split(';') {
+            let outcome_str = outcome_str.trim();
+            outcomes.push(Outcome::parse(line, outcome_str)?);
         }
+        Ok(Self { id, outcomes })
     }
 }
 
@@ -42,36 +36,41 @@ struct Outcome {
     pub blue: u32,
 }
 
-impl FromStr for Outcome {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl Outcome {
+    fn parse(line: &Line, s: &str) -> Result<Self, ParseError> {
         let mut outcome = Outcome::default();
         for cube_str in s.split(',') {
             let cube_str = cube_str.trim();
-            if let Some((part1, part2)) = cube_str.split_once(' ') {
-                let n = part1.parse()
-                    .context("Cubes count must be an integer")?;
-                match part2 {
-                    "red" => outcome.red = n,
-                    "green" => outcome.green = n,
-                    "blue" => outcome.blue =n,
-                    _ => {}
-                }
+            let (part1, part2) = cube_str.split_once(' ')
+                .ok_or_else(|| line.error(cube_str, "Expect cubes count and color"))?;
+            let n = part1.parse()
+                .map_err(|_| line.error(part1, "Cubes count must be an integer"))?;
+            match part2 {
+                "red" => outcome.red = n,
+                "green" => outcome.green = n,
+                "blue" => outcome.blue =n,
+                _ => return Err(line.error(part2, format!("Unknown color '{part2}'"))),
             }
         }
         Ok(outcome)
     }
 }
 
-pub fn solve_1(input: &str) -> crate::PuzzleResult {
-    let mut res = 0;
-    'outer: for line in input.split('\n') {
-        let line = line.trim();
-        if line.is_empty() {
+fn parse(input: &str) -> Result<Vec<Game>, ParseError> {
+    let mut games = vec!();
+    for line in parse_error::lines(input) {
+        let text = line.text.trim();
+        if text.is_empty() {
             continue;
         }
-        let game = line.parse::<Game>()?;
+        games.push(Game::parse(&line, text)?);
+    }
+    Ok(games)
+}
+
+pub fn solve_1(input: &str) -> crate::PuzzleResult {
+    let mut res = 0;
+    'outer: for game in parse(input)? {
         for outcome in game.outcomes {
             if outcome.red > RED_CUBES {
                 continue 'outer;
@@ -85,17 +84,12 @@ pub fn solve_1(input: &str) -> crate::PuzzleResult {
         }
         res += game.id;
     }
-    Ok(res.to_string())
+    Ok(res.into())
 }
 
 pub fn solve_2(input: &str) -> crate::PuzzleResult {
     let mut res = 0;
-    for line in input.split('\n') {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-        let game = line.parse::<Game>()?;
+    for game in parse(input)? {
         let mut min_outcome = Outcome::default();
         for outcome in game.outcomes {
             if outcome.red > min_outcome.red {
@@ -110,30 +104,35 @@ pub fn solve_2(input: &str) -> crate::PuzzleResult {
         }
         res += min_outcome.red * min_outcome.green * min_outcome.blue;
     }
-    Ok(res.to_string())
+    Ok(res.into())
 }
 
+const EXAMPLE_INPUT: &str = indoc!{"
+    Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+    Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+    Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+    Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+    Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+"};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT, Some(8), Some(2286)),
+];
+
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use super::*;
 
-    const EXAMPLE_INPUT: &'static str = indoc!{"
-        Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-        Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-        Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
-    "};
-
     #[test]
     fn test_parse_outcome() -> anyhow::Result<()> {
+        let line = Line { number: 1, text: "3 blue, 4 red" };
         assert_eq!(
-            "3 blue, 4 red".parse::<Outcome>()?,
+            Outcome::parse(&line, line.text)?,
             Outcome { red: 4, blue: 3, ..Default::default() }
         );
+        let line = Line { number: 1, text: "2 green" };
         assert_eq!(
-            "2 green".parse::<Outcome>()?,
+            Outcome::parse(&line, line.text)?,
             Outcome { green: 2, ..Default::default() }
         );
         Ok(())
@@ -142,7 +141,7 @@ mod tests {
     #[test]
     fn test_parse_game() -> anyhow::Result<()> {
         assert_eq!(
-            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green".parse::<Game>().unwrap(),
+            parse("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green")?[0],
             Game {
                 id: 1,
                 outcomes: vec!(
@@ -158,8 +157,8 @@ mod tests {
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT)?,
-            "8".to_string()
+            solve_1(EXAMPLE_INPUT)?.value,
+            8.into()
         );
         Ok(())
     }
@@ -167,9 +166,18 @@ mod tests {
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_2(EXAMPLE_INPUT)?,
-            "2286".to_string()
+            solve_2(EXAMPLE_INPUT)?.value,
+            2286.into()
         );
         Ok(())
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = solve_1("Game 1: 3 blue\nGame 2: 1 red, 2 pink\n").unwrap_err();
+        assert_eq!(err.to_string(), "Unknown color 'pink' at line 2, column 18");
+
+        let err = solve_1("Game x: 3 blue\n").unwrap_err();
+        assert_eq!(err.to_string(), "Game identifier must be an integer at line 1, column 6");
+    }
 }
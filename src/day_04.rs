@@ -2,6 +2,11 @@ use std::collections::HashSet;
 
 use anyhow::Context;
 
+use indoc::indoc;
+
+use crate::parse_error::{self, Line, ParseError};
+use crate::registry::Example;
+
 #[derive(Debug)]
 struct Card {
     winning_nums: u32,
@@ -11,24 +16,23 @@ struct Card {
 pub fn solve_1(input: &str) -> crate::PuzzleResult {
     let res = parse_cards(input)?.iter()
         .filter(|c| c.winning_nums > 0)
-        .fold(0, |acc, c| acc + (1 << (c.winning_nums - 1)))
-        .to_string();
-    Ok(res)
+        .fold(0, |acc, c| acc + (1 << (c.winning_nums - 1)));
+    Ok(res.into())
 }
 
-fn parse_cards(input: &str) -> anyhow::Result<Vec<Card>> {
-    input.lines()
+fn parse_cards(input: &str) -> Result<Vec<Card>, ParseError> {
+    parse_error::lines(input)
         .map(parse_card)
         .filter_map(Result::transpose)
         .collect()
 }
 
-fn parse_card(line: &str) -> anyhow::Result<Option<Card>> {
-    if let Some((_, card_str)) = line.split_once(':') {
+fn parse_card(line: Line) -> Result<Option<Card>, ParseError> {
+    if let Some((_, card_str)) = line.text.split_once(':') {
         let card_str = card_str.trim();
         if let Some((my_nums_str, total_nums_str)) = card_str.split_once('|') {
-            let my_nums = parse_nums(my_nums_str.trim())?;
-            let total_nums = parse_nums(total_nums_str.trim())?;
+            let my_nums = parse_nums(&line, my_nums_str.trim())?;
+            let total_nums = parse_nums(&line, total_nums_str.trim())?;
             let winning_nums = total_nums.intersection(&my_nums).count();
             return Ok(Some(Card { winning_nums: winning_nums as u32, copies: 1 }));
         }
@@ -36,10 +40,10 @@ fn parse_card(line: &str) -> anyhow::Result<Option<Card>> {
     Ok(None)
 }
 
-fn parse_nums(s: &str) -> anyhow::Result<HashSet<u32>> {
+fn parse_nums(line: &Line, s: &str) -> Result<HashSet<u32>, ParseError> {
     s.split(' ')
         .filter(|v| !v.is_empty())
-        .map(|v| v.parse().context("Expect integer"))
+        .map(|v| v.parse().map_err(|_| line.error(v, format!("Expect integer: {v}"))))
         .collect()
 }
 
@@ -55,28 +59,31 @@ pub fn solve_2(input: &str) -> crate::PuzzleResult {
         sum += card.copies;
     }
 
-    Ok(sum.to_string())
+    Ok(sum.into())
 }
 
+const EXAMPLE_INPUT: &str = indoc!{"
+    Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+    Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+    Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+    Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+    Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+    Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+"};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT, Some(13), Some(30)),
+];
+
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use super::*;
 
-    const EXAMPLE_INPUT: &'static str = indoc!{"
-        Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-        Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-        Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-        Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-        Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-        Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
-    "};
-
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT)?,
-            "13".to_string()
+            solve_1(EXAMPLE_INPUT)?.value,
+            13.into()
         );
         Ok(())
     }
@@ -84,9 +91,17 @@ mod tests {
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_2(EXAMPLE_INPUT)?,
-            "30".to_string()
+            solve_2(EXAMPLE_INPUT)?.value,
+            30.into()
         );
         Ok(())
     }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse_cards("Card 1: 41 48 | 83 86\nCard 2: 13 3x | 61 30\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 12));
+        assert_eq!(err.message, "Expect integer: 3x");
+        assert_eq!(err.snippet, "Card 2: 13 3x | 61 30");
+    }
 }
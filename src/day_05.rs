@@ -1,255 +1,209 @@
+use std::fmt;
+
 use anyhow::Context;
 
-#[derive(Debug)]
-struct MapRange {
-    dst_start: u64,
-    src_start: u64,
-    len: u64,
-}
+use indoc::indoc;
 
-impl MapRange {
-    fn src_end(&self) -> u64 {
-        self.src_start + self.len
-    }
+use crate::interval::{self, Interval, PiecewiseMap};
+use crate::parse_error::{self, Line, ParseError};
+use crate::registry::Example;
+use crate::solution::Solution;
 
-    fn shift_dist(&self) -> i64 {
-        self.dst_start as i64 - self.src_start as i64
-    }
-}
+pub fn solve_1(input: &str) -> crate::PuzzleResult {
+    let (_, seeds, maps) = parse(input)?;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-struct SeedRange {
-    start: u64,
-    len: u64,
-}
+    let almanac = Almanac::compose(&maps);
 
-impl SeedRange {
-    fn end(&self) -> u64 {
-        self.start + self.len
-    }
+    let min_loc = seeds.into_iter()
+        .map(|seed| almanac.location(seed))
+        .min()
+        .context("Expect seeds")?;
+    Ok(min_loc.into())
+}
 
-    fn shift(&self, dist: i64) -> Self {
-        Self {
-            start: (self.start as i64 + dist) as u64,
-            len: self.len,
-        }
+pub fn solve_2(input: &str) -> crate::PuzzleResult {
+    let (seeds_line, seeds, maps) = parse(input)?;
+    if seeds.len() % 2 != 0 {
+        let err = seeds_line.error(seeds_line.text, "Expect pairs of seed range start and length");
+        return Err(err.into());
     }
-}
+    let seed_ranges = seeds
+        .chunks(2)
+        .map(|v| Interval::with_len(v[0], v[1]))
+        .collect::<Vec<_>>();
 
-pub fn solve_1(input: &str) -> crate::PuzzleResult {
-    let mut lines = input.lines();
+    let almanac = Almanac::compose(&maps);
 
-    let seeds = parse_seeds(&mut lines)?;
-    assert!(
-        matches!(lines.next(), Some(""))
+    let num_seeds = seed_ranges.iter().map(Interval::len).sum::<i64>();
+    let loc_ranges = interval::normalize(
+        seed_ranges.iter().flat_map(|seed_range| almanac.locations(seed_range))
     );
 
-    let maps = parse_maps(&mut lines)?;
-
-    let mut locs = vec!();
-    for seed in seeds {
-        locs.push(map_seed_to_loc(seed, &maps));
-    }
-
-    Ok(locs.iter().min().unwrap().to_string())
+    let min_loc = loc_ranges.first()
+        .context("Expect seeds")?
+        .start;
+    let seed = almanac.seeds(min_loc)
+        .into_iter()
+        .find(|&seed| seed_ranges.iter().any(|seed_range| seed_range.contains(seed)))
+        .context("Expect seed for the lowest location")?;
+    let solution = Solution::from(min_loc)
+        .with_diagnostic("Number of seeds", num_seeds)
+        .with_diagnostic("Number of location ranges", loc_ranges.len())
+        .with_diagnostic("Seed for the lowest location", seed)
+        .with_visualization(almanac.to_string());
+    Ok(solution)
 }
 
-pub fn solve_2(input: &str) -> crate::PuzzleResult {
-    let mut lines = input.lines();
+/// All the maps of the almanac composed into a single seed to location map
+#[derive(Debug)]
+struct Almanac {
+    seed_to_location: PiecewiseMap,
+}
 
-    let seeds = parse_seeds(&mut lines)?;
-    let mut seed_ranges = seeds
-        .chunks(2)
-        .map(|v| SeedRange { start: v[0], len: v[1] })
-        .collect::<Vec<_>>();
-    assert!(
-        matches!(lines.next(), Some(""))
-    );
+impl Almanac {
+    fn compose(maps: &[PiecewiseMap]) -> Self {
+        let seed_to_location = maps.iter()
+            .fold(PiecewiseMap::default(), |composed, map| composed.then(map));
+        Self { seed_to_location }
+    }
 
-    let maps = parse_maps(&mut lines)?;
+    fn location(&self, seed: i64) -> i64 {
+        self.seed_to_location.map_point(seed)
+    }
 
-    for map in maps.iter() {
-        let mut mapped_seed_ranges = vec!();
-        let mut not_mapped_seed_ranges = seed_ranges.clone();
-        for map_range in map.iter() {
-            let (_mapped_seed_ranges, _not_mapped_seed_ranges) = map_seed_ranges(&not_mapped_seed_ranges, map_range);
-            mapped_seed_ranges.extend(_mapped_seed_ranges);
-            not_mapped_seed_ranges = _not_mapped_seed_ranges;
-        }
-        seed_ranges.clear();
-        seed_ranges.extend(mapped_seed_ranges);
-        seed_ranges.extend(not_mapped_seed_ranges);
+    fn locations(&self, seeds: &Interval) -> Vec<Interval> {
+        self.seed_to_location.map_interval(seeds)
     }
 
-    let mut min_loc = u64::MAX;
-    for loc_range in seed_ranges {
-        if loc_range.start < min_loc {
-            min_loc = loc_range.start;
-        }
+    /// Seeds that are planted at the location
+    fn seeds(&self, location: i64) -> Vec<i64> {
+        self.seed_to_location.preimage(location)
     }
-    Ok(min_loc.to_string())
 }
 
-fn map_seed_ranges(
-    seed_ranges: &Vec<SeedRange>,
-    map_range: &MapRange,
-) -> (Vec<SeedRange>, Vec<SeedRange>) {
-    let mut mapped_seed_ranges = vec!();
-    let mut not_mapped_seed_ranges = vec!();
-    for seed_range in seed_ranges {
-        let (mapped_seed_range, outside_seed_ranges) = map_seed_range(seed_range, map_range);
-        if let Some(mapped_seed_range) = mapped_seed_range {
-            mapped_seed_ranges.push(mapped_seed_range);
+impl fmt::Display for Almanac {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "seed -> location")?;
+        self.seed_to_location.fmt(f)?;
+        match self.seed_to_location.inverse() {
+            Ok(location_to_seed) => {
+                writeln!(f, "\nlocation -> seed")?;
+                location_to_seed.fmt(f)
+            }
+            Err(e) => writeln!(f, "\nCannot invert: {e}"),
         }
-        not_mapped_seed_ranges.extend(outside_seed_ranges);
     }
-    (mapped_seed_ranges, not_mapped_seed_ranges)
 }
 
-fn map_seed_range(seed_range: &SeedRange, map_range: &MapRange) -> (Option<SeedRange>, Vec<SeedRange>) {
-    // Seed range is fully outside of the map range
-    if seed_range.end() <= map_range.src_start ||
-        seed_range.start >= map_range.src_end()
-    {
-        return (None, vec!(*seed_range));
-    }
-
-    // Seed range is fully inside of the map range
-    if seed_range.start >= map_range.src_start &&
-        seed_range.end() <= map_range.src_end()
-    {
-        let new_seed_range_start = seed_range.start + map_range.dst_start - map_range.src_start;
-        return (
-            Some(SeedRange { start: new_seed_range_start, len: seed_range.len }),
-            vec!()
-        )
-    }
-
-    let mut outside_seed_ranges = vec!();
-    let mut intermediate_seed_range = *seed_range;
-    // Cut left side of the seed range
-    if seed_range.start < map_range.src_start {
-        let outside_len = map_range.src_start - seed_range.start;
-        outside_seed_ranges.push(
-            SeedRange { start: seed_range.start, len: outside_len }
-        );
-        intermediate_seed_range.start = map_range.src_start;
-        intermediate_seed_range.len -= outside_len;
-    }
-    // Cut right side of the seed range
-    if seed_range.end() > map_range.src_end() {
-        let outside_len = seed_range.end() - map_range.src_end();
-        outside_seed_ranges.push(
-            SeedRange { start: map_range.src_end(), len: outside_len }
-        );
-        intermediate_seed_range.len -= outside_len;
+/// Returns the seeds line together with the seeds to point at it later
+fn parse(input: &str) -> anyhow::Result<(Line<'_>, Vec<i64>, Vec<PiecewiseMap>)> {
+    let mut lines = parse_error::lines(input);
+    let seeds_line = lines.next().context("Expect seeds line")?;
+    let seeds = parse_seeds(&seeds_line)?;
+    if let Some(line) = lines.next() {
+        if !line.text.trim().is_empty() {
+            return Err(line.error(line.text, "Expect empty line after seeds").into());
+        }
     }
-
-    (
-        Some(intermediate_seed_range.shift(map_range.shift_dist())),
-        outside_seed_ranges
-    )
+    let maps = parse_maps(lines)?;
+    Ok((seeds_line, seeds, maps))
 }
 
-fn parse_seeds<'a>(mut lines: impl Iterator<Item = &'a str>) -> anyhow::Result<Vec<u64>> {
-    let seeds_line = lines.next().context("Expect more lines")?;
-    let (_name, seeds_str) =  seeds_line.split_once(':').context("Expect ':' at first line")?;
-    seeds_str.trim().split(' ')
-        .map(|v| v.parse().context("Expect integer"))
+fn parse_seeds(line: &Line) -> Result<Vec<i64>, ParseError> {
+    let seeds_str = line.text.strip_prefix("seeds:")
+        .ok_or_else(|| line.error(line.text, "Expect 'seeds:'"))?;
+    seeds_str.split_whitespace()
+        .map(|v| v.parse().map_err(|_| line.error(v, "Expect integer")))
         .collect()
 }
 
 fn parse_maps<'a>(
-    mut lines: impl Iterator<Item = &'a str>
-) -> anyhow::Result<Vec<Vec<MapRange>>> {
+    mut lines: impl Iterator<Item = Line<'a>>
+) -> Result<Vec<PiecewiseMap>, ParseError> {
     let mut maps = vec!();
-    while let Some(_map_name_line) = lines.next() {
-        let mut map = vec!();
-        loop {
-            if let Some(map_str) = lines.next() {
-                let map_str = map_str.trim();
-                if map_str.is_empty() {
-                    break;
-                }
-
-                let mut map_range_parts = map_str.splitn(3, ' ')
-                    .map(str::parse)
-                    .filter_map(Result::ok);
-                let dst_start = map_range_parts.next().context("Expect destination start")?;
-                let src_start = map_range_parts.next().context("Expect source start")?;
-                let len = map_range_parts.next().context("Expect map range")?;
-                let map_range = MapRange { dst_start, src_start, len };
-                map.push(map_range);
-            } else {
+    while let Some(name_line) = lines.next() {
+        let name_str = name_line.text.trim();
+        if name_str.is_empty() {
+            continue;
+        }
+        let name = name_str.strip_suffix(" map:")
+            .ok_or_else(|| name_line.error(name_str, "Expect map name"))?;
+        let mut map_ranges = vec!();
+        for line in lines.by_ref() {
+            let map_str = line.text.trim();
+            if map_str.is_empty() {
                 break;
             }
+            map_ranges.push(parse_map_range(&line, map_str)?);
         }
+        let map = PiecewiseMap::new(map_ranges)
+            .map_err(|e| name_line.error(name, format!("Invalid {name} map: {e}")))?;
         maps.push(map);
     }
     Ok(maps)
 }
 
-fn map_seed_to_loc(seed: u64, maps: &Vec<Vec<MapRange>>) -> u64 {
-    let mut seed_loc = seed;
-
-    'maps: for map in maps.iter() {
-        for map_range in map.iter() {
-            if seed_loc >= map_range.src_start && seed_loc < map_range.src_start + map_range.len {
-                seed_loc = map_range.dst_start + (seed_loc - map_range.src_start);
-                continue 'maps;
-            }
-        }
-    }
-    seed_loc
+/// Destination start, source start and length of the range
+fn parse_map_range(line: &Line, map_str: &str) -> Result<(Interval, i64), ParseError> {
+    let nums = map_str.split_whitespace()
+        .map(|v| v.parse::<i64>().map_err(|_| line.error(v, "Expect integer")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let [dst_start, src_start, len] = nums[..] else {
+        return Err(line.error(map_str, "Expect destination start, source start and range length"));
+    };
+    Ok((Interval::with_len(src_start, len), dst_start - src_start))
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use test_log::test;
-    use super::*;
+const EXAMPLE_INPUT: &str = indoc!{"
+    seeds: 79 14 55 13
 
-    const EXAMPLE_INPUT: &'static str = indoc!{"
-        seeds: 79 14 55 13
+    seed-to-soil map:
+    50 98 2
+    52 50 48
 
-        seed-to-soil map:
-        50 98 2
-        52 50 48
+    soil-to-fertilizer map:
+    0 15 37
+    37 52 2
+    39 0 15
 
-        soil-to-fertilizer map:
-        0 15 37
-        37 52 2
-        39 0 15
+    fertilizer-to-water map:
+    49 53 8
+    0 11 42
+    42 0 7
+    57 7 4
 
-        fertilizer-to-water map:
-        49 53 8
-        0 11 42
-        42 0 7
-        57 7 4
+    water-to-light map:
+    88 18 7
+    18 25 70
 
-        water-to-light map:
-        88 18 7
-        18 25 70
+    light-to-temperature map:
+    45 77 23
+    81 45 19
+    68 64 13
 
-        light-to-temperature map:
-        45 77 23
-        81 45 19
-        68 64 13
+    temperature-to-humidity map:
+    0 69 1
+    1 0 69
 
-        temperature-to-humidity map:
-        0 69 1
-        1 0 69
+    humidity-to-location map:
+    60 56 37
+    56 93 4
+"};
 
-        humidity-to-location map:
-        60 56 37
-        56 93 4
-    "};
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT, Some(35), Some(46)),
+];
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+    use super::*;
 
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT)?,
-            "35".to_string()
+            solve_1(EXAMPLE_INPUT)?.value,
+            35.into()
         );
         Ok(())
     }
@@ -257,40 +211,84 @@ mod tests {
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_2(EXAMPLE_INPUT)?,
-            "46".to_string()
+            solve_2(EXAMPLE_INPUT)?.value,
+            46.into()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_almanac() -> anyhow::Result<()> {
+        let (_, _, maps) = parse(EXAMPLE_INPUT)?;
+        let almanac = Almanac::compose(&maps);
+
+        assert_eq!(
+            [79, 14, 55, 13].map(|seed| almanac.location(seed)),
+            [82, 43, 86, 35]
         );
+        assert_eq!(almanac.seeds(46), vec!(82));
+        assert_eq!(almanac.locations(&Interval::new(82, 83)), vec!(Interval::new(46, 47)));
+
+        let location_to_seed = almanac.seed_to_location.inverse()?;
+        for location in [35, 43, 46, 82, 86] {
+            assert_eq!(almanac.location(location_to_seed.map_point(location)), location);
+        }
+        assert!(almanac.to_string().starts_with("seed -> location\n"));
         Ok(())
     }
 
     #[test]
-    fn test_map_seed_range() {
+    fn test_map_seed_range() -> anyhow::Result<()> {
         // 7..17 -> 20..30 (10)  - map range from 7 to 20 with length 10
-        let map_range = MapRange { dst_start: 20, src_start: 7, len: 10 };
+        let map = PiecewiseMap::new([(Interval::with_len(7, 10), 20 - 7)])?;
 
         assert_eq!(
-            map_seed_range(&SeedRange { start: 5, len: 2 }, &map_range),
-            (None, vec!(SeedRange { start: 5, len: 2 }))
+            map.map_interval(&Interval::with_len(5, 2)),
+            vec!(Interval::with_len(5, 2))
+        );
+        assert_eq!(
+            map.map_interval(&Interval::with_len(30, 93)),
+            vec!(Interval::with_len(30, 93))
         );
         assert_eq!(
-            map_seed_range(&SeedRange { start: 30, len: 93 }, &map_range),
-            (None, vec!(SeedRange { start: 30, len: 93 }))
+            map.map_interval(&Interval::with_len(7, 10)),
+            vec!(Interval::with_len(20, 10))
         );
         assert_eq!(
-            map_seed_range(&SeedRange { start: 7, len: 10 }, &map_range),
-            (Some(SeedRange { start: 20, len: 10 }), vec!())
+            map.map_interval(&Interval::with_len(11, 3)),
+            vec!(Interval::with_len(24, 3))
         );
         assert_eq!(
-            map_seed_range(&SeedRange { start: 11, len: 3 }, &map_range),
-            (Some(SeedRange { start: 24, len: 3 }), vec!())
+            map.map_interval(&Interval::with_len(6, 10)),
+            vec!(Interval::with_len(20, 9), Interval::with_len(6, 1))
         );
         assert_eq!(
-            map_seed_range(&SeedRange { start: 6, len: 10 }, &map_range),
-            (Some(SeedRange { start: 20, len: 9 }), vec!(SeedRange { start: 6, len: 1 }))
+            map.map_interval(&Interval::with_len(16, 10)),
+            vec!(Interval::with_len(29, 1), Interval::with_len(17, 9))
         );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = solve_1("seeds: 79 1x\n").unwrap_err();
+        assert_eq!(err.to_string(), "Expect integer at line 1, column 11");
+
+        let err = solve_1("seeds: 79\nseed-to-soil map:\n").unwrap_err();
+        assert_eq!(err.to_string(), "Expect empty line after seeds at line 2, column 1");
+
+        let err = solve_1("seeds: 79\n\nseed-to-soil map:\n50 98\n").unwrap_err();
         assert_eq!(
-            map_seed_range(&SeedRange { start: 16, len: 10 }, &map_range),
-            (Some(SeedRange { start: 29, len: 1 }), vec!(SeedRange { start: 17, len: 9 }))
+            err.to_string(),
+            "Expect destination start, source start and range length at line 4, column 1"
         );
+
+        let err = solve_1("seeds: 79\n\nseed-to-soil map:\n50 98 2\n0 99 5\n").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert!(err.message.starts_with("Invalid seed-to-soil map: "), "{}", err.message);
+
+        let err = solve_2("seeds: 79 14 55\n").unwrap_err();
+        assert_eq!(err.to_string(), "Expect pairs of seed range start and length at line 1, column 1");
     }
 }
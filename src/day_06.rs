@@ -2,20 +2,21 @@ use std::cmp::Ordering;
 
 use anyhow::Context;
 
+use indoc::indoc;
+
+use crate::parse_error::{self, Line, ParseError};
+use crate::registry::Example;
+
 pub fn solve_1(input: &str) -> crate::PuzzleResult {
-    let mut lines = input.lines();
-    let time_line = lines.next().context("Expect time line")?;
-    let times = if let Some((_, times_str)) = time_line.split_once(':') {
-        parse_nums(times_str.trim())?
-    } else {
-        anyhow::bail!("Missing time line")
-    };
-    let dist_line = lines.next().context("Expect distance line")?;
-    let distances = if let Some((_, distances_str)) = dist_line.split_once(':') {
-        parse_nums(distances_str.trim())?
-    } else {
-        anyhow::bail!("Missing distance line")
-    };
+    let mut lines = parse_error::lines(input);
+    let (time_line, times_str) = parse_line(&mut lines, "Time")?;
+    let times = parse_nums(&time_line, times_str)?;
+    let (dist_line, distances_str) = parse_line(&mut lines, "Distance")?;
+    let distances = parse_nums(&dist_line, distances_str)?;
+    if distances.len() != times.len() {
+        let err = dist_line.error(distances_str, format!("Expect {} distances", times.len()));
+        return Err(err.into());
+    }
     // log::debug!("Times: {times:?}");
     // log::debug!("Distances: {distances:?}");
 
@@ -34,23 +35,15 @@ pub fn solve_1(input: &str) -> crate::PuzzleResult {
         }
         res *= num_of_winning_outcomes;
     }
-    Ok(res.to_string())
+    Ok(res.into())
 }
 
 pub fn solve_2(input: &str) -> crate::PuzzleResult {
-    let mut lines = input.lines();
-    let time_line = lines.next().context("Expect time line")?;
-    let time = if let Some((_, times_str)) = time_line.split_once(':') {
-        times_str.trim().replace(" ", "").parse::<u64>().context("Expect time")?
-    } else {
-        anyhow::bail!("Missing time line")
-    };
-    let dist_line = lines.next().context("Expect distance line")?;
-    let dist = if let Some((_, distances_str)) = dist_line.split_once(':') {
-        distances_str.trim().replace(" ", "").parse::<u64>().context("Expect distance")?
-    } else {
-        anyhow::bail!("Missing distance line")
-    };
+    let mut lines = parse_error::lines(input);
+    let (time_line, time_str) = parse_line(&mut lines, "Time")?;
+    let time = parse_kerned_num(&time_line, time_str)?;
+    let (dist_line, dist_str) = parse_line(&mut lines, "Distance")?;
+    let dist = parse_kerned_num(&dist_line, dist_str)?;
     // log::debug!("Time: {time:?}");
     // log::debug!("Distance: {dist:?}");
 
@@ -65,15 +58,37 @@ pub fn solve_2(input: &str) -> crate::PuzzleResult {
     } else {
         0
     };
-    Ok(res.to_string())
+    Ok(res.into())
+}
+
+/// Returns the line and the values after its label
+fn parse_line<'a>(
+    lines: &mut impl Iterator<Item = Line<'a>>,
+    label: &str,
+) -> anyhow::Result<(Line<'a>, &'a str)> {
+    let line = lines.next().with_context(|| format!("Expect '{label}:' line"))?;
+    let values_str = line.text.strip_prefix(label)
+        .and_then(|s| s.strip_prefix(':'))
+        .ok_or_else(|| line.error(line.text, format!("Expect '{label}:'")))?;
+    Ok((line, values_str))
 }
 
-fn parse_nums(s: &str) -> anyhow::Result<Vec<u64>> {
+fn parse_nums(line: &Line, s: &str) -> Result<Vec<u64>, ParseError> {
     s.split(' ')
-        .filter_map(|v| if v.is_empty() { None } else { Some(v.parse().context("Expect integer")) })
+        .filter(|v| !v.is_empty())
+        .map(|v| v.parse().map_err(|_| line.error(v, "Expect integer")))
         .collect()
 }
 
+/// Parses digits separated by spaces as a single number
+fn parse_kerned_num(line: &Line, s: &str) -> Result<u64, ParseError> {
+    let s = s.trim();
+    if let Some((ix, _)) = s.char_indices().find(|(_, c)| !c.is_ascii_digit() && *c != ' ') {
+        return Err(line.error(&s[ix..], "Expect digit"));
+    }
+    s.replace(' ', "").parse().map_err(|_| line.error(s, "Expect integer"))
+}
+
 /**
  * x^2 + bx + c = 0
  * x = b/2 ± √(b^2/4 - c)
@@ -94,24 +109,28 @@ fn solve_quadratic(b: f64, c: f64) -> (Option<f64>, Option<f64>) {
     }
 }
 
+const EXAMPLE_INPUT: &str = indoc!{"
+    Time:      7  15   30
+    Distance:  9  40  200
+"};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT, Some(288), Some(71503)),
+];
+
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use test_log::test;
+    use crate::registry::Part;
     use crate::util;
 
     use super::*;
 
-    const EXAMPLE_INPUT: &'static str = indoc!{"
-        Time:      7  15   30
-        Distance:  9  40  200
-    "};
-
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT)?,
-            "288".to_string()
+            solve_1(EXAMPLE_INPUT)?.value,
+            288.into()
         );
         Ok(())
     }
@@ -126,15 +145,15 @@ mod tests {
         };
 
         log::debug!("{input}");
-        log::warn!("{}", solve_1(&input)?);
+        util::check_answer(day, Part::One, &solve_1(&input)?)?;
         Ok(())
     }
 
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_2(EXAMPLE_INPUT)?,
-            "71503".to_string()
+            solve_2(EXAMPLE_INPUT)?.value,
+            71503.into()
         );
         Ok(())
     }
@@ -149,7 +168,22 @@ mod tests {
         };
 
         log::debug!("{input}");
-        log::warn!("{}", solve_2(&input)?);
+        util::check_answer(day, Part::Two, &solve_2(&input)?)?;
         Ok(())
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = solve_1("Time:      7  15   30\nDistance:  9  4x  200\n").unwrap_err();
+        assert_eq!(err.to_string(), "Expect integer at line 2, column 15");
+
+        let err = solve_1("Time:      7  15   30\nDistance:  9  40\n").unwrap_err();
+        assert_eq!(err.to_string(), "Expect 3 distances at line 2, column 10");
+
+        let err = solve_2("Time:      7  15   30\nDist:  9  40  200\n").unwrap_err();
+        assert_eq!(err.to_string(), "Expect 'Distance:' at line 2, column 1");
+
+        let err = solve_2("Time:      7  1.5   30\nDistance:  9  40  200\n").unwrap_err();
+        assert_eq!(err.to_string(), "Expect digit at line 1, column 16");
+    }
 }
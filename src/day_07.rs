@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 
-use anyhow::Context;
-
 use counter::Counter;
 
+use indoc::indoc;
+
+use crate::parse_error::{self, Line, ParseError};
+use crate::registry::Example;
+
 const CARDS: &'static [char] = &[
     '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A'
 ];
@@ -52,7 +55,7 @@ struct Hand {
 pub fn solve_1(input: &str) -> crate::PuzzleResult {
     let card_ordinals = build_card_ordinals(CARDS);
 
-    let lines = input.lines();
+    let lines = parse_error::lines(input);
     let mut hands_with_bids = vec!();
     for line in lines {
         if let Some((cards, bid)) = parse_cards_and_bid(line, &card_ordinals)? {
@@ -64,13 +67,13 @@ pub fn solve_1(input: &str) -> crate::PuzzleResult {
     }
     hands_with_bids.sort_by_key(|(h, _)| *h);
 
-    Ok(calc_total_score(&hands_with_bids).to_string())
+    Ok(calc_total_score(&hands_with_bids).into())
 }
 
 pub fn solve_2(input: &str) -> crate::PuzzleResult {
     let card_ordinals = build_card_ordinals(CARDS_WITH_JOKER);
 
-    let lines = input.lines();
+    let lines = parse_error::lines(input);
     let mut hands_with_bids = vec!();
     for line in lines {
         if let Some((cards, bid)) = parse_cards_and_bid(line, &card_ordinals)? {
@@ -83,7 +86,7 @@ pub fn solve_2(input: &str) -> crate::PuzzleResult {
     }
     hands_with_bids.sort_by_key(|(h, _)| *h);
 
-    Ok(calc_total_score(&hands_with_bids).to_string())
+    Ok(calc_total_score(&hands_with_bids).into())
 }
 
 fn build_card_ordinals(cards_order: &[char]) -> HashMap<char, u8> {
@@ -95,33 +98,37 @@ fn build_card_ordinals(cards_order: &[char]) -> HashMap<char, u8> {
 }
 
 fn parse_cards_and_bid(
-    line: &str,
+    line: Line,
     card_ords: &HashMap<char, u8>
-) -> anyhow::Result<Option<(Cards, u64)>> {
-    let line = line.trim();
-    if line.is_empty() {
+) -> Result<Option<(Cards, u64)>, ParseError> {
+    let text = line.text.trim();
+    if text.is_empty() {
         return Ok(None);
     }
-    if let Some((cards_str, bid_str)) = line.split_once(' ') {
-        let cards_str = cards_str.trim();
-        let cards = parse_cards(cards_str, &card_ords)?;
-        let bid_str = bid_str.trim();
-        let bid = bid_str.parse().context("Expect bid number")?;
-        Ok(Some((cards, bid)))
-    } else {
-        Ok(None)
-    }
+    let (cards_str, bid_str) = text.split_once(' ')
+        .ok_or_else(|| line.error(text, "Expect cards and bid separated by space"))?;
+    let cards_str = cards_str.trim();
+    let cards = parse_cards(&line, cards_str, &card_ords)?;
+    let bid_str = bid_str.trim();
+    let bid = bid_str.parse()
+        .map_err(|_| line.error(bid_str, "Expect bid number"))?;
+    Ok(Some((cards, bid)))
 }
 
 fn parse_cards(
+    line: &Line,
     s: &str,
     card_ords: &HashMap<char, u8>
-) -> anyhow::Result<Cards> {
-    s.chars()
-        .map(|c| card_ords[&c] as u8)
-        .collect::<Vec<_>>()
+) -> Result<Cards, ParseError> {
+    s.char_indices()
+        .map(|(ix, c)| {
+            card_ords.get(&c)
+                .copied()
+                .ok_or_else(|| line.error(&s[ix..], format!("Unknown card '{c}'")))
+        })
+        .collect::<Result<Vec<_>, _>>()?
         .try_into()
-        .map_err(|_| anyhow::anyhow!("Expect 5 cards in hand"))
+        .map_err(|_| line.error(s, "Expect 5 cards in hand"))
 }
 
 fn promote_jokers(cards: &Cards) -> Cards {
@@ -147,26 +154,30 @@ fn calc_total_score(hands: &Vec<(Hand, u64)>) -> u64 {
     res
 }
 
+const EXAMPLE_INPUT: &str = indoc!{"
+    32T3K 765
+    T55J5 684
+    KK677 28
+    KTJJT 220
+    QQQJA 483
+"};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT, Some(6440), Some(5905)),
+];
+
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use test_log::test;
+    use crate::registry::Part;
     use crate::util;
     use super::*;
 
-    const EXAMPLE_INPUT: &'static str = indoc!{"
-        32T3K 765
-        T55J5 684
-        KK677 28
-        KTJJT 220
-        QQQJA 483
-    "};
-
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT)?,
-            "6440".to_string()
+            solve_1(EXAMPLE_INPUT)?.value,
+            6440.into()
         );
         Ok(())
     }
@@ -180,15 +191,15 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_1(&input)?);
+        util::check_answer(day, Part::One, &solve_1(&input)?)?;
         Ok(())
     }
 
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_2(EXAMPLE_INPUT)?,
-            "5905".to_string()
+            solve_2(EXAMPLE_INPUT)?.value,
+            5905.into()
         );
         Ok(())
     }
@@ -202,7 +213,24 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_2(&input)?);
+        util::check_answer(day, Part::Two, &solve_2(&input)?)?;
         Ok(())
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = solve_1("32T3K 765\nT55X5 684\n").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (2, 4));
+        assert_eq!(err.message, "Unknown card 'X'");
+
+        let err = solve_1("32T3K 765\n32T3 684\n").unwrap_err();
+        assert_eq!(err.to_string(), "Expect 5 cards in hand at line 2, column 1");
+
+        let err = solve_2("32T3K 765\n  KTJJT\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Expect cards and bid separated by space at line 2, column 3"
+        );
+    }
 }
@@ -1,120 +1,360 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
 
 use anyhow::Context;
 
 use num::integer::lcm;
 
+use indoc::indoc;
+
+use crate::cycle::{self, Cycle};
+use crate::parse_error;
+use crate::registry::Example;
+use crate::search;
+use crate::solution::Solution;
+
 pub fn solve_1(input: &str) -> crate::PuzzleResult {
     let (navigation, network) = parse(input)?;
 
-    let steps = find_num_steps("AAA", navigation, &network, |n| n == "ZZZ");
-    Ok(steps.to_string())
+    let steps = find_num_steps("AAA", navigation, &network, |n| n == "ZZZ")?;
+    Ok(steps.into())
 }
 
 pub fn solve_2(input: &str) -> crate::PuzzleResult {
     let (navigation, network) = parse(input)?;
 
-    let steps = network.keys()
-        .filter(|n| n.ends_with("A"))
-        .map(|n| find_num_steps(n, navigation, &network, |n| n.ends_with("Z")))
-        .fold(1, lcm);
+    let mut starts = network.keys()
+        .copied()
+        .filter(|n| n.ends_with('A'))
+        .collect::<Vec<_>>();
+    starts.sort();
+    let ghosts = starts.iter()
+        .map(|start| analyze_ghost(start, navigation, &network, |n| n.ends_with('Z')))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let steps = find_common_hit(&ghosts)?;
 
-    Ok(steps.to_string())
+    let mut solution = Solution::from(steps);
+    for ghost in &ghosts {
+        solution = solution.with_diagnostic(&format!("Ghost {}", ghost.start), ghost);
+    }
+    let lcm_steps = ghosts.iter()
+        .map(|ghost| ghost.first_hit().unwrap_or(0) as u64)
+        .fold(1, lcm);
+    solution = solution.with_diagnostic("LCM of first terminal steps", lcm_steps);
+    if let Some(objection) = ghosts.iter().find_map(GhostCycle::lcm_objection) {
+        solution = solution.with_diagnostic("LCM is not reliable", objection);
+    }
+    Ok(solution)
 }
 
-fn parse(input: &str) -> anyhow::Result<(&str, HashMap<&str, (&str, &str)>)> {
-    let mut lines = input.lines();
-    let navigation = lines.next().context("Expect navigation line")?;
+type Network<'a> = HashMap<&'a str, (&'a str, &'a str)>;
+
+/// Every node the network refers to is checked to be defined
+fn parse(input: &str) -> anyhow::Result<(&str, Network<'_>)> {
+    let mut lines = parse_error::lines(input);
+    let navigation_line = lines.next().context("Expect navigation line")?;
+    let navigation = navigation_line.text.trim();
+    if let Some((ix, c)) = navigation.char_indices().find(|(_, c)| !matches!(c, 'L' | 'R')) {
+        let err = navigation_line.error(&navigation[ix..], format!("Unknown instruction '{c}'"));
+        return Err(err.into());
+    }
+    if navigation.is_empty() {
+        return Err(navigation_line.error(navigation, "Expect instructions").into());
+    }
+
     let mut network = HashMap::new();
+    let mut references = vec!();
     for line in lines {
-        let line = line.trim();
-        if line.is_empty() {
+        let text = line.text.trim();
+        if text.is_empty() {
             continue;
         }
-        if let Some((node, neighbours)) = line.split_once('=') {
-            let node = node.trim();
-            if let Some((left_node, right_node)) = neighbours.split_once(',') {
-                let left_node = left_node.trim();
-                let left_node = left_node.strip_prefix("(").context("Expect '('")?;
-                let right_node = right_node.trim();
-                let right_node = right_node.strip_suffix(")").context("Expect ')'")?;
-                network.insert(node, (left_node, right_node));
-            }
+        let (node, left_node, right_node) = parse_node(text)
+            .ok_or_else(|| line.error(text, "Expect 'NODE = (LEFT, RIGHT)'"))?;
+        if network.insert(node, (left_node, right_node)).is_some() {
+            return Err(line.error(node, format!("Node {node} is defined twice")).into());
+        }
+        references.push((line, left_node));
+        references.push((line, right_node));
+    }
+    for (line, node) in references {
+        if !network.contains_key(node) {
+            return Err(line.error(node, format!("Unknown node {node}")).into());
         }
     }
 
     Ok((navigation, network))
 }
 
-pub fn find_num_steps(
+fn parse_node(text: &str) -> Option<(&str, &str, &str)> {
+    let (node, neighbours) = text.split_once('=')?;
+    let (left_node, right_node) = neighbours.trim()
+        .strip_prefix('(')?
+        .strip_suffix(')')?
+        .split_once(',')?;
+    Some((node.trim(), left_node.trim(), right_node.trim()))
+}
+
+/// Nodes are not checked here, `parse` makes sure every referenced node is defined
+fn next_node<'a>(network: &Network<'a>, node: &str, instruction: char) -> &'a str {
+    let (left_node, right_node) = network[node];
+    if instruction == 'L' { left_node } else { right_node }
+}
+
+/// Counts steps from the node to a terminal one.
+/// A state is the current node with the index of the next instruction,
+/// the walk is over when a state repeats
+fn find_num_steps(
     node: &str,
     rules: &str,
-    network: &HashMap<&str, (&str, &str)>,
+    network: &Network,
     terminate: impl Fn(&str) -> bool,
-) -> u64 {
-    let mut current_node = node;
-    let mut steps = 0;
-    for (i, instruction) in rules.chars().cycle().enumerate() {
-        let (left_node, right_node) = network[current_node];
-        current_node = match instruction {
-            'L' => left_node,
-            'R' => right_node,
-            _ => continue,
+) -> anyhow::Result<u64> {
+    if !network.contains_key(node) {
+        anyhow::bail!("Unknown start node {node}");
+    }
+    let instructions = rules.chars().collect::<Vec<_>>();
+    let search = search::bfs(
+        [(node, 0)],
+        |&(cur_node, instruction_ix)| {
+            let next_instruction_ix = (instruction_ix + 1) % instructions.len();
+            Some((next_node(network, cur_node, instructions[instruction_ix]), next_instruction_ix))
+        },
+        |&(cur_node, _)| terminate(cur_node),
+    );
+    match search.goal_cost() {
+        Some(steps) => Ok(steps),
+        None => anyhow::bail!(
+            "Path from {node} loops forever, it repeats after {} steps without reaching a terminal node",
+            search.visited().count()
+        ),
+    }
+}
+
+/// Path of a ghost until its state, the node and the instruction index, repeats
+#[derive(Debug)]
+struct GhostCycle<'a> {
+    start: &'a str,
+    cycle: Cycle,
+    /// Steps on terminal nodes before the path repeats
+    hits: Vec<usize>,
+}
+
+impl GhostCycle<'_> {
+    fn is_hit(&self, step: usize) -> bool {
+        self.hits.binary_search(&self.cycle.reduce(step)).is_ok()
+    }
+
+    /// Steps after the start that reach every state of the path once.
+    /// A cycle from step 0 returns to the start state only at step `len`
+    fn distinct_steps(&self) -> Range<usize> {
+        let Cycle { offset, len } = self.cycle;
+        1..offset.max(1) + len
+    }
+
+    fn first_hit(&self) -> Option<usize> {
+        self.distinct_steps().find(|&step| self.is_hit(step))
+    }
+
+    /// Explains why the LCM of first terminal steps is not the answer in general.
+    /// It is only when a ghost stands on a terminal node exactly every N steps
+    fn lcm_objection(&self) -> Option<String> {
+        let Cycle { offset, len } = self.cycle;
+        let hits = self.distinct_steps()
+            .filter(|&step| self.is_hit(step))
+            .collect::<Vec<_>>();
+        let objection = match hits[..] {
+            [] => format!("ghost {} never reaches a terminal node", self.start),
+            [hit] if hit < offset => format!(
+                "ghost {} reaches a terminal node at step {hit} before its cycle starts at step {offset}",
+                self.start
+            ),
+            [hit] if hit != len => format!(
+                "ghost {} reaches a terminal node at step {hit} but its path repeats every {len} steps",
+                self.start
+            ),
+            [_] => return None,
+            _ => format!(
+                "ghost {} reaches terminal nodes at steps {hits:?} within a cycle of {len} steps",
+                self.start
+            ),
         };
-        if terminate(current_node) {
-            steps = i + 1;
-            break;
+        Some(objection)
+    }
+}
+
+impl fmt::Display for GhostCycle<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cycle of {} steps from step {}, terminal nodes at steps {:?}",
+            self.cycle.len, self.cycle.offset, self.hits
+        )
+    }
+}
+
+fn analyze_ghost<'a>(
+    start: &'a str,
+    rules: &str,
+    network: &Network<'a>,
+    terminate: impl Fn(&str) -> bool,
+) -> anyhow::Result<GhostCycle<'a>> {
+    let instructions = rules.chars().collect::<Vec<_>>();
+    let history = cycle::detect(
+        (start, 0),
+        usize::MAX,
+        |&state| state,
+        |&(node, instruction_ix)| {
+            let next_instruction_ix = (instruction_ix + 1) % instructions.len();
+            (next_node(network, node, instructions[instruction_ix]), next_instruction_ix)
+        },
+    );
+
+    let cycle = history.cycle.context("Expect the ghost to walk in circles")?;
+    let hits = history.states.iter()
+        .enumerate()
+        .filter(|(_, (node, _))| terminate(node))
+        .map(|(step, _)| step)
+        .collect();
+    Ok(GhostCycle { start, cycle, hits })
+}
+
+/// Limits the work when many ghosts stand on terminal nodes several times per cycle
+const MAX_CONGRUENCES: usize = 10_000;
+
+/// Finds the first step when all the ghosts stand on terminal nodes
+fn find_common_hit(ghosts: &[GhostCycle]) -> anyhow::Result<u64> {
+    let max_offset = ghosts.iter()
+        .map(|ghost| ghost.cycle.offset)
+        .max()
+        .context("Expect ghosts")?;
+
+    // Until every ghost enters its cycle steps are checked one by one
+    if let Some(step) = (1..max_offset).find(|&step| ghosts.iter().all(|g| g.is_hit(step))) {
+        return Ok(step as u64);
+    }
+
+    // Then every ghost restricts the step to a set of remainders modulo its cycle length
+    let mut congruences = vec!((0, 1));
+    for ghost in ghosts {
+        let Cycle { offset, len } = ghost.cycle;
+        let remainders = ghost.hits.iter()
+            .filter(|&&hit| hit >= offset)
+            .map(|&hit| (hit % len) as i128)
+            .collect::<Vec<_>>();
+        let mut next_congruences = vec!();
+        for &congruence in &congruences {
+            for &remainder in &remainders {
+                next_congruences.extend(crt(congruence, (remainder, len as i128))?);
+            }
+        }
+        next_congruences.sort();
+        next_congruences.dedup();
+        if next_congruences.len() > MAX_CONGRUENCES {
+            anyhow::bail!(
+                "Ghosts have more than {MAX_CONGRUENCES} combinations of terminal steps, \
+                 ghost {} multiplies them by {}",
+                ghost.start, remainders.len()
+            );
         }
+        congruences = next_congruences;
     }
-    steps as u64
+
+    let min_step = max_offset.max(1) as i128;
+    congruences.into_iter()
+        .map(|(remainder, modulus)| {
+            if remainder >= min_step {
+                remainder
+            } else {
+                remainder + (min_step - remainder + modulus - 1) / modulus * modulus
+            }
+        })
+        .min()
+        .and_then(|step| u64::try_from(step).ok())
+        .context("Ghosts never stand on terminal nodes at the same time")
 }
 
+/// Combines `x = a1 (mod m1)` and `x = a2 (mod m2)` into a single congruence.
+/// Moduli do not have to be coprime. Returns `None` if the congruences have no common solution
+fn crt((a1, m1): (i128, i128), (a2, m2): (i128, i128)) -> anyhow::Result<Option<(i128, i128)>> {
+    let (g, p, _) = extended_gcd(m1, m2);
+    if (a2 - a1) % g != 0 {
+        return Ok(None);
+    }
+    let overflow = || anyhow::anyhow!("Combined cycle of {m1} and {m2} steps is too long");
+    let m = (m1 / g).checked_mul(m2).ok_or_else(overflow)?;
+    // p * m1 = g (mod m2)
+    let k = ((a2 - a1) / g % (m2 / g)).checked_mul(p).ok_or_else(overflow)? % (m2 / g);
+    // |m1 * k| < m so the sum fits
+    Ok(Some(((a1 + m1 * k).rem_euclid(m), m)))
+}
+
+/// Returns `(g, x, y)` where `g = gcd(a, b) = a * x + b * y`
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - a / b * y)
+    }
+}
+
+const EXAMPLE_INPUT_1: &str = indoc!{"
+    RL
+
+    AAA = (BBB, CCC)
+    BBB = (DDD, EEE)
+    CCC = (ZZZ, GGG)
+    DDD = (DDD, DDD)
+    EEE = (EEE, EEE)
+    GGG = (GGG, GGG)
+    ZZZ = (ZZZ, ZZZ)
+"};
+const EXAMPLE_INPUT_2: &str = indoc!{"
+    LLR
+
+    AAA = (BBB, BBB)
+    BBB = (AAA, ZZZ)
+    ZZZ = (ZZZ, ZZZ)
+"};
+const EXAMPLE_INPUT_3: &str = indoc!{"
+    LR
+
+    11A = (11B, XXX)
+    11B = (XXX, 11Z)
+    11Z = (11B, XXX)
+    22A = (22B, XXX)
+    22B = (22C, 22C)
+    22C = (22Z, 22Z)
+    22Z = (22B, 22B)
+    XXX = (XXX, XXX)
+"};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT_1, Some(2), None),
+    Example::new(EXAMPLE_INPUT_2, Some(6), None),
+    Example::new(EXAMPLE_INPUT_3, None, Some(6)),
+];
+
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use test_log::test;
+    use crate::parse_error::ParseError;
+    use crate::registry::Part;
     use crate::util;
     use super::*;
 
-    const EXAMPLE_INPUT_1: &'static str = indoc!{"
-        RL
-
-        AAA = (BBB, CCC)
-        BBB = (DDD, EEE)
-        CCC = (ZZZ, GGG)
-        DDD = (DDD, DDD)
-        EEE = (EEE, EEE)
-        GGG = (GGG, GGG)
-    "};
-    const EXAMPLE_INPUT_2: &'static str = indoc!{"
-        LLR
-
-        AAA = (BBB, BBB)
-        BBB = (AAA, ZZZ)
-        ZZZ = (ZZZ, ZZZ)
-    "};
-    const EXAMPLE_INPUT_3: &'static str = indoc!{"
-        LR
-
-        11A = (11B, XXX)
-        11B = (XXX, 11Z)
-        11Z = (11B, XXX)
-        22A = (22B, XXX)
-        22B = (22C, 22C)
-        22C = (22Z, 22Z)
-        22Z = (22B, 22B)
-        XXX = (XXX, XXX)
-    "};
-
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT_1)?,
-            "2".to_string()
+            solve_1(EXAMPLE_INPUT_1)?.value,
+            2.into()
         );
         assert_eq!(
-            solve_1(EXAMPLE_INPUT_2)?,
-            "6".to_string()
+            solve_1(EXAMPLE_INPUT_2)?.value,
+            6.into()
         );
         Ok(())
     }
@@ -128,15 +368,15 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_1(&input)?);
+        util::check_answer(day, Part::One, &solve_1(&input)?)?;
         Ok(())
     }
 
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_2(EXAMPLE_INPUT_3)?,
-            "6".to_string()
+            solve_2(EXAMPLE_INPUT_3)?.value,
+            6.into()
         );
         Ok(())
     }
@@ -150,7 +390,136 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_2(&input)?);
+        util::check_answer(day, Part::Two, &solve_2(&input)?)?;
         Ok(())
     }
+
+    #[test]
+    fn test_solve_2_when_lcm_is_wrong() -> anyhow::Result<()> {
+        // 1A hits 1Z every 4 steps, 2A hits 2Z at steps 2, 5, 8 and so on
+        let input = indoc!{"
+            L
+
+            1A = (1B, XX)
+            1B = (1C, XX)
+            1C = (1D, XX)
+            1D = (1Z, XX)
+            1Z = (1B, XX)
+            2A = (2B, XX)
+            2B = (2Z, XX)
+            2Z = (2C, XX)
+            2C = (2B, XX)
+            XX = (XX, XX)
+        "};
+        let solution = solve_2(input)?;
+        assert_eq!(solution.value, 8.into());
+        let diagnostics = solution.diagnostics.iter()
+            .map(|d| (d.name.as_str(), d.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            vec!(
+                ("Ghost 1A", "cycle of 4 steps from step 1, terminal nodes at steps [4]"),
+                ("Ghost 2A", "cycle of 3 steps from step 1, terminal nodes at steps [2]"),
+                ("LCM of first terminal steps", "4"),
+                (
+                    "LCM is not reliable",
+                    "ghost 2A reaches a terminal node at step 2 but its path repeats every 3 steps"
+                ),
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_common_hit() {
+        let ghost = |offset, len, hits: &[usize]| GhostCycle {
+            start: "",
+            cycle: Cycle { offset, len },
+            hits: hits.to_vec(),
+        };
+        // Both ghosts stand on terminal nodes at step 3 before entering their cycles
+        assert_eq!(find_common_hit(&[ghost(5, 2, &[3, 5]), ghost(4, 3, &[3, 4])]).ok(), Some(3));
+        // Steps 5 + 2k and 4 + 3k first meet at step 7
+        assert_eq!(find_common_hit(&[ghost(5, 2, &[5]), ghost(4, 3, &[4])]).ok(), Some(7));
+        // Even and odd steps never meet
+        assert_eq!(
+            find_common_hit(&[ghost(0, 2, &[0]), ghost(0, 2, &[1])]).unwrap_err().to_string(),
+            "Ghosts never stand on terminal nodes at the same time"
+        );
+        assert_eq!(crt((2, 6), (5, 9)).ok(), Some(Some((14, 18))));
+        assert_eq!(crt((1, 4), (2, 6)).ok(), Some(None));
+
+        // Pairwise coprime cycles of about 2^40 steps, their product does not fit into i128
+        let lens = [1 << 40, (1 << 40) + 1, (1 << 40) + 3, (1 << 40) + 5];
+        let ghosts = lens.map(|len| ghost(0, len, &[len - 1]));
+        assert_eq!(
+            find_common_hit(&ghosts).unwrap_err().to_string(),
+            format!(
+                "Combined cycle of {} and {} steps is too long",
+                lens[0] as i128 * lens[1] as i128 * lens[2] as i128,
+                lens[3]
+            )
+        );
+
+        // Ghosts stand on terminal nodes at every step but the first one of their cycles
+        let ghosts = [("11A", 11), ("22A", 13), ("33A", 17), ("44A", 19)]
+            .map(|(start, len)| GhostCycle { start, ..ghost(0, len, &(1..len).collect::<Vec<_>>()) });
+        assert_eq!(
+            find_common_hit(&ghosts).unwrap_err().to_string(),
+            "Ghosts have more than 10000 combinations of terminal steps, ghost 44A multiplies them by 18"
+        );
+    }
+
+    #[test]
+    fn test_lcm_objection() {
+        let ghost = |offset, len, hits: &[usize]| GhostCycle {
+            start: "11A",
+            cycle: Cycle { offset, len },
+            hits: hits.to_vec(),
+        };
+        // Step 4 is the same state as step 2
+        assert_eq!(ghost(2, 2, &[2]).first_hit(), Some(2));
+        assert_eq!(ghost(2, 2, &[2]).lcm_objection(), None);
+        // Step 3 is the same state as step 0
+        assert_eq!(ghost(0, 3, &[0]).first_hit(), Some(3));
+        assert_eq!(ghost(0, 3, &[0]).lcm_objection(), None);
+        assert_eq!(
+            ghost(2, 3, &[1, 3]).lcm_objection().as_deref(),
+            Some("ghost 11A reaches terminal nodes at steps [1, 3] within a cycle of 3 steps")
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = solve_1("LR\n\nAAA = (BBB, ZZZ)\nBBB = (CCC, AAA)\nZZZ = (ZZZ, ZZZ)\n").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (4, 8));
+        assert_eq!(err.message, "Unknown node CCC");
+
+        let err = solve_1("LXR\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n").unwrap_err();
+        assert_eq!(err.to_string(), "Unknown instruction 'X' at line 1, column 2");
+
+        let err = solve_1("L\n\nAAA = ZZZ, ZZZ\n").unwrap_err();
+        assert_eq!(err.to_string(), "Expect 'NODE = (LEFT, RIGHT)' at line 3, column 1");
+    }
+
+    #[test]
+    fn test_solve_1_when_path_loops() {
+        let input = indoc!{"
+            LR
+
+            AAA = (BBB, ZZZ)
+            BBB = (AAA, AAA)
+            ZZZ = (ZZZ, ZZZ)
+        "};
+        assert_eq!(
+            solve_1(input).unwrap_err().to_string(),
+            "Path from AAA loops forever, it repeats after 2 steps without reaching a terminal node"
+        );
+        assert_eq!(
+            solve_1("L\n\nBBB = (BBB, BBB)\n").unwrap_err().to_string(),
+            "Unknown start node AAA"
+        );
+    }
 }
@@ -1,5 +1,10 @@
 use anyhow::Context;
 
+use indoc::indoc;
+
+use crate::parse_error::{self, ParseError};
+use crate::registry::Example;
+
 pub fn solve_1(input: &str) -> crate::PuzzleResult {
     let nums = parse(input)?;
     let res = nums.iter()
@@ -11,7 +16,7 @@ pub fn solve_1(input: &str) -> crate::PuzzleResult {
                 (Err(e), _) => Err(e),
             }
         })?;
-    Ok(res.to_string())
+    Ok(res.into())
 }
 
 pub fn solve_2(input: &str) -> crate::PuzzleResult {
@@ -25,7 +30,7 @@ pub fn solve_2(input: &str) -> crate::PuzzleResult {
                 (Err(e), _) => Err(e),
             }
         })?;
-    Ok(res.to_string())
+    Ok(res.into())
 }
 
 fn calc_next_num(nums: &[i64]) -> anyhow::Result<i64> {
@@ -68,18 +73,17 @@ fn calc_prev_num(nums: &[i64]) -> anyhow::Result<i64> {
     Ok(first_num)
 }
 
-fn parse(input: &str) -> anyhow::Result<Vec<Vec<i64>>> {
+fn parse(input: &str) -> Result<Vec<Vec<i64>>, ParseError> {
     let mut nums = vec!();
-    let lines = input.lines();
+    let lines = parse_error::lines(input);
     for line in lines {
-        let line = line.trim();
-        if line.is_empty() {
+        let text = line.text.trim();
+        if text.is_empty() {
             continue;
         }
         let mut line_nums = vec!();
-        for num_str in line.split(' ') {
-            let num_str = num_str.trim();
-            line_nums.push(num_str.parse().context("Expect number")?);
+        for num_str in text.split_whitespace() {
+            line_nums.push(num_str.parse().map_err(|_| line.error(num_str, "Expect number"))?);
         }
         nums.push(line_nums);
     }
@@ -87,25 +91,28 @@ fn parse(input: &str) -> anyhow::Result<Vec<Vec<i64>>> {
     Ok(nums)
 }
 
+const EXAMPLE_INPUT: &str = indoc!{"
+    0 3 6 9 12 15
+    1 3 6 10 15 21
+    10 13 16 21 30 45
+"};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT, Some(114), Some(2)),
+];
 
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use test_log::test;
+    use crate::registry::Part;
     use crate::util;
     use super::*;
 
-    const EXAMPLE_INPUT: &'static str = indoc!{"
-        0 3 6 9 12 15
-        1 3 6 10 15 21
-        10 13 16 21 30 45
-    "};
-
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT)?,
-            "114".to_string()
+            solve_1(EXAMPLE_INPUT)?.value,
+            114.into()
         );
         Ok(())
     }
@@ -119,15 +126,15 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_1(&input)?);
+        util::check_answer(day, Part::One, &solve_1(&input)?)?;
         Ok(())
     }
 
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_2(EXAMPLE_INPUT)?,
-            "2".to_string()
+            solve_2(EXAMPLE_INPUT)?.value,
+            2.into()
         );
         Ok(())
     }
@@ -141,7 +148,13 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_2(&input)?);
+        util::check_answer(day, Part::Two, &solve_2(&input)?)?;
         Ok(())
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = solve_1("0 3 6 9\n1 3 six 10\n").unwrap_err();
+        assert_eq!(err.to_string(), "Expect number at line 2, column 5");
+    }
 }
@@ -1,10 +1,19 @@
 use std::collections::HashMap;
 
+use anyhow::Context;
+
+use indoc::indoc;
+
+use crate::grid::Grid;
+use crate::point::{Direction, Point};
+use crate::registry::Example;
+use crate::search;
+
 pub fn solve_1(input: &str) -> crate::PuzzleResult {
     let (area, start_loc) = parse(input)?;
 
     let pipe = calc_pipe_from_start(&area, start_loc)?;
-    Ok((pipe.len() / 2).to_string())
+    Ok((pipe.len / 2).into())
 }
 
 pub fn solve_2(input: &str) -> crate::PuzzleResult {
@@ -12,291 +21,219 @@ pub fn solve_2(input: &str) -> crate::PuzzleResult {
 
     let pipe = calc_pipe_from_start(&area, start_loc)?;
     let mut tiles_inside = 0;
-    for row_ix in 0..area.len() {
-        for col_ix in 0..area[0].len() {
-            if pipe.contains_key(&(row_ix, col_ix)) {
-                continue;
-            }
+    for loc in area.points() {
+        if pipe.tiles.contains_key(&loc) {
+            continue;
+        }
 
-            let mut intersections = 0;
-            let mut touch_via = None;
-            for test_row_ix in (0..row_ix).rev() {
-                 match pipe.get(&(test_row_ix, col_ix)) {
-                     Some('-') => {
+        let mut intersections = 0;
+        let mut touch_via = None;
+        for test_loc in area.walk(loc, Direction::Up) {
+             match pipe.tiles.get(&test_loc) {
+                 Some('-') => {
+                     intersections += 1;
+                 }
+                 Some('J') => {
+                     touch_via = Some('J');
+                 }
+                 Some('L') => {
+                     touch_via = Some('L');
+                 }
+                 Some('F') => {
+                     if let Some('J') = touch_via {
                          intersections += 1;
                      }
-                     Some('J') => {
-                         touch_via = Some('J');
-                     }
-                     Some('L') => {
-                         touch_via = Some('L');
-                     }
-                     Some('F') => {
-                         if let Some('J') = touch_via {
-                             intersections += 1;
-                         }
-                         touch_via = None;
-                     }
-                     Some('7') => {
-                         if let Some('L') = touch_via {
-                             intersections += 1;
-                         }
-                         touch_via = None;
+                     touch_via = None;
+                 }
+                 Some('7') => {
+                     if let Some('L') = touch_via {
+                         intersections += 1;
                      }
-                     _ => {}
+                     touch_via = None;
                  }
+                 _ => {}
+             }
 
-            }
-            if intersections % 2 == 1 {
-                tiles_inside += 1;
-            }
+        }
+        if intersections % 2 == 1 {
+            tiles_inside += 1;
         }
     }
-    Ok(tiles_inside.to_string())
+    Ok(tiles_inside.into())
 }
 
-fn parse(input: &str) -> anyhow::Result<(Vec<Vec<char>>, (usize, usize))> {
-    let mut num_cols = 0;
-    let mut rows = vec!();
-    let mut start_loc = (0, 0);
-    for (row_ix, line) in input.lines().enumerate() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-        let row = line.chars().enumerate()
-            .map(|(col_ix, c)| {
-                if c == 'S' {
-                    start_loc = (row_ix, col_ix);
-                }
-                c
-            })
-            .collect::<Vec<_>>();
-        if num_cols == 0 {
-            num_cols = row.len();
-        } else if row.len() != num_cols {
-            anyhow::bail!("Expect {num_cols} columns")
-        }
-        rows.push(row);
-    }
-    Ok((rows, start_loc))
+fn parse(input: &str) -> anyhow::Result<(Grid<char>, Point)> {
+    let area = Grid::parse(input)?;
+    let start_loc = area.find(|&c| c == 'S')
+        .context("Expect start point")?;
+    Ok((area, start_loc))
 }
 
-fn calc_pipe_from_start(
-    area: &Vec<Vec<char>>,
-    start_loc: (usize, usize),
-) -> anyhow::Result<HashMap<(usize, usize), char>> {
-    let rows = area.len();
-    let cols = area[0].len();
+/// Directions that the pipe tile connects
+fn connections(tile: char) -> Option<[Direction; 2]> {
+    use Direction::*;
 
-    let mut neighbour_locs = vec!();
-    if start_loc.0 >= 1 {
-        let top_loc = (start_loc.0 - 1, start_loc.1);
-        match area[top_loc.0][top_loc.1] {
-            '|' | 'F' | '7' => {
-                neighbour_locs.push(top_loc);
-            }
-            _ => {}
-        }
-    }
-    if start_loc.0 < rows - 1 {
-        let bottom_loc = (start_loc.0 + 1, start_loc.1);
-        match area[bottom_loc.0][bottom_loc.1] {
-            '|' | 'L' | 'J' => {
-                neighbour_locs.push(bottom_loc);
-            }
-            _ => {}
-        }
-    }
-    if start_loc.1 >= 1 {
-        let left_loc = (start_loc.0, start_loc.1 - 1);
-        match area[left_loc.0][left_loc.1] {
-            '-' | 'F' | 'L' => {
-                neighbour_locs.push(left_loc);
-            }
-            _ => {}
-        }
-    }
-    if start_loc.1 < cols - 1 {
-        let right_loc = (start_loc.0, start_loc.1 + 1);
-        match area[right_loc.0][right_loc.1] {
-            '-' | '7' | 'J' => {
-                neighbour_locs.push(right_loc);
-            }
-            _ => {}
-        }
-    }
+    let dirs = match tile {
+        '|' => [Up, Down],
+        '-' => [Left, Right],
+        'L' => [Up, Right],
+        'J' => [Up, Left],
+        '7' => [Down, Left],
+        'F' => [Down, Right],
+        _ => return None,
+    };
+    Some(dirs)
+}
 
-    if neighbour_locs.len() != 2 {
-        anyhow::bail!("No connected pipes to the start point");
-    }
-    neighbour_locs.sort();
-    calc_pipe(area, start_loc, neighbour_locs[0])
+/// Loop of pipe tiles with the start tile replaced by the pipe it stands for
+struct Pipe {
+    tiles: HashMap<Point, char>,
+    len: usize,
 }
 
-fn calc_pipe(
-    area: &Vec<Vec<char>>,
-    start_loc: (usize, usize),
-    cur_loc: (usize, usize),
-) -> anyhow::Result<HashMap<(usize, usize), char>> {
-    let first_loc = cur_loc;
-    let first_tile = area[cur_loc.0][cur_loc.1];
-    let mut prev_loc = start_loc;
-    let mut cur_loc = cur_loc;
-    let mut pipe = HashMap::new();
-    let (last_loc, last_tile) = loop {
-        pipe.insert(cur_loc, area[cur_loc.0][cur_loc.1]);
-        let next_loc = calc_next_loc(area, cur_loc, prev_loc)?;
-        prev_loc = cur_loc;
-        let prev_tile = area[prev_loc.0][prev_loc.1];
-        cur_loc = next_loc;
-        if area[cur_loc.0][cur_loc.1] == 'S' {
-            break (prev_loc, prev_tile);
-        }
-    };
+fn calc_pipe_from_start(area: &Grid<char>, start_loc: Point) -> anyhow::Result<Pipe> {
+    // Directions from the start to the neighbours that lead back to it
+    let start_dirs = area.neighbors4(start_loc)
+        .filter_map(|loc| {
+            let back_dir = connections(area[loc])?
+                .into_iter()
+                .find(|&dir| loc.step(dir) == Some(start_loc))?;
+            Some(back_dir.reverse())
+        })
+        .collect::<Vec<_>>();
 
-    let start_tile = if first_loc.0 == last_loc.0 {
-        '-'
-    } else if first_loc.1 == last_loc.1 {
-        '|'
-    } else if first_loc.1 < last_loc.1 {
-        match (first_tile, last_tile) {
-            ('|' | '7' | 'F', '-' | '7' | 'J') => 'L',
-            _ => '7',
-        }
-    } else if first_loc.1 > last_loc.1 {
-        match (first_tile, last_tile) {
-            ('-' | 'J' | '7', '|' | 'J' | 'L') => 'F',
-            _ => 'J'
-        }
-    } else {
-        anyhow::bail!("Cannot find tile for start point")
-    };
-    pipe.insert(start_loc, start_tile);
+    if start_dirs.len() != 2 {
+        anyhow::bail!("No connected pipes to the start point");
+    }
+    let start_tile = "|-LJ7F".chars()
+        .find(|&tile| {
+            connections(tile).is_some_and(|dirs| start_dirs.iter().all(|dir| dirs.contains(dir)))
+        })
+        .context("Cannot find tile for start point")?;
+    let tile_at = |loc: Point| if loc == start_loc { start_tile } else { area[loc] };
+
+    // The search stops at the first tile that does not connect to its neighbours
+    let mut leak = None;
+    let search = search::bfs(
+        [start_loc],
+        |&loc| {
+            connections(tile_at(loc))
+                .into_iter()
+                .flatten()
+                .filter_map(move |dir| area.step(loc, dir))
+        },
+        |&loc| {
+            leak = find_leak(area, tile_at, loc);
+            leak.is_some()
+        },
+    );
+    if let Some(leak) = leak {
+        anyhow::bail!(leak);
+    }
 
-    Ok(pipe)
+    // Both ways around the loop meet at the farthest tile
+    let max_distance = search.visited()
+        .map(|(_, distance)| distance)
+        .max()
+        .unwrap_or_default();
+    let tiles = search.visited()
+        .map(|(&loc, _)| (loc, tile_at(loc)))
+        .collect();
+    Ok(Pipe { tiles, len: 2 * max_distance as usize })
 }
 
-fn calc_next_loc(
-    area: &Vec<Vec<char>>,
-    cur_loc: (usize, usize),
-    prev_loc: (usize, usize),
-) -> anyhow::Result<(usize, usize)> {
-    let cur_tile = area[cur_loc.0][cur_loc.1];
-    let next_loc = match cur_tile {
-        '|' => {
-            if prev_loc.0 < cur_loc.0 {
-                (cur_loc.0 + 1, cur_loc.1)
-            } else {
-                (cur_loc.0 - 1, cur_loc.1)
-            }
-        }
-        '-' => {
-            if prev_loc.1 < cur_loc.1 {
-                (cur_loc.0, cur_loc.1 + 1)
-            } else {
-                (cur_loc.0, cur_loc.1 - 1)
-            }
-        }
-        'L' => {
-            if prev_loc.0 < cur_loc.0 {
-                (cur_loc.0, cur_loc.1 + 1)
-            } else {
-                (cur_loc.0 - 1, cur_loc.1)
-            }
-        }
-        'F' => {
-            if prev_loc.1 > cur_loc.1 {
-                (cur_loc.0 + 1, cur_loc.1)
-            } else {
-                (cur_loc.0, cur_loc.1 + 1)
-            }
-        }
-        '7' => {
-            if prev_loc.1 < cur_loc.1 {
-                (cur_loc.0 + 1, cur_loc.1)
-            } else {
-                (cur_loc.0, cur_loc.1 - 1)
-            }
-        }
-        'J' => {
-            if prev_loc.1 < cur_loc.1 {
-                (cur_loc.0 - 1, cur_loc.1)
-            } else {
-                (cur_loc.0, cur_loc.1 - 1)
-            }
-        }
-        _ => anyhow::bail!("Pipe is broken"),
+/// Describes why the pipe at `loc` is not part of a closed loop
+fn find_leak(area: &Grid<char>, tile_at: impl Fn(Point) -> char, loc: Point) -> Option<&'static str> {
+    let Some(dirs) = connections(tile_at(loc)) else {
+        return Some("Pipe is broken");
     };
-    Ok(next_loc)
+    for dir in dirs {
+        let Some(next_loc) = area.step(loc, dir) else {
+            return Some("Pipe leads out of the area");
+        };
+        let connects_back = connections(tile_at(next_loc))
+            .is_some_and(|next_dirs| next_dirs.contains(&dir.reverse()));
+        if !connects_back {
+            return Some("Pipe is broken");
+        }
+    }
+    None
 }
 
+const EXAMPLE_INPUT_1: &str = indoc!{"
+    .....
+    .S-7.
+    .|.|.
+    .L-J.
+    .....
+"};
+const EXAMPLE_INPUT_2: &str = indoc!{"
+    ..F7.
+    .FJ|.
+    SJ.L7
+    |F--J
+    LJ...
+"};
+const EXAMPLE_INPUT_3: &str = indoc!{"
+    ...........
+    .S-------7.
+    .|F-----7|.
+    .||.....||.
+    .||.....||.
+    .|L-7.F-J|.
+    .|..|.|..|.
+    .L--J.L--J.
+    ...........
+"};
+const EXAMPLE_INPUT_4: &str = indoc!{"
+    .F----7F7F7F7F-7....
+    .|F--7||||||||FJ....
+    .||.FJ||||||||L7....
+    FJL7L7LJLJ||LJ.L-7..
+    L--J.L7...LJS7F-7L7.
+    ....F-J..F7FJ|L7L7L7
+    ....L7.F7||L7|.L7L7|
+    .....|FJLJ|FJ|F7|.LJ
+    ....FJL-7.||.||||...
+    ....L---J.LJ.LJLJ...
+"};
+const EXAMPLE_INPUT_5: &str = indoc!{"
+    FF7FSF7F7F7F7F7F---7
+    L|LJ||||||||||||F--J
+    FL-7LJLJ||||||LJL-77
+    F--JF--7||LJLJ7F7FJ-
+    L---JF-JLJ.||-FJLJJ7
+    |F|F-JF---7F7-L7L|7|
+    |FFJF7L7F-JF7|JL---7
+    7-L-JL7||F7|L7F-7F7|
+    L.L7LFJ|||||FJL7||LJ
+    L7JLJL-JLJLJL--JLJ.L
+"};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT_1, Some(4), Some(1)),
+    Example::new(EXAMPLE_INPUT_2, Some(8), Some(1)),
+    Example::new(EXAMPLE_INPUT_3, None, Some(4)),
+    Example::new(EXAMPLE_INPUT_4, None, Some(8)),
+    Example::new(EXAMPLE_INPUT_5, None, Some(10)),
+];
+
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use test_log::test;
+    use crate::registry::Part;
     use crate::util;
     use super::*;
 
-    const EXAMPLE_INPUT_1: &'static str = indoc!{"
-        .....
-        .S-7.
-        .|.|.
-        .L-J.
-        .....
-    "};
-    const EXAMPLE_INPUT_2: &'static str = indoc!{"
-        ..F7.
-        .FJ|.
-        SJ.L7
-        |F--J
-        LJ...
-    "};
-    const EXAMPLE_INPUT_3: &'static str = indoc!{"
-        ...........
-        .S-------7.
-        .|F-----7|.
-        .||.....||.
-        .||.....||.
-        .|L-7.F-J|.
-        .|..|.|..|.
-        .L--J.L--J.
-        ...........
-    "};
-    const EXAMPLE_INPUT_4: &'static str = indoc!{"
-        .F----7F7F7F7F-7....
-        .|F--7||||||||FJ....
-        .||.FJ||||||||L7....
-        FJL7L7LJLJ||LJ.L-7..
-        L--J.L7...LJS7F-7L7.
-        ....F-J..F7FJ|L7L7L7
-        ....L7.F7||L7|.L7L7|
-        .....|FJLJ|FJ|F7|.LJ
-        ....FJL-7.||.||||...
-        ....L---J.LJ.LJLJ...
-    "};
-    const EXAMPLE_INPUT_5: &'static str = indoc!{"
-        FF7FSF7F7F7F7F7F---7
-        L|LJ||||||||||||F--J
-        FL-7LJLJ||||||LJL-77
-        F--JF--7||LJLJ7F7FJ-
-        L---JF-JLJ.||-FJLJJ7
-        |F|F-JF---7F7-L7L|7|
-        |FFJF7L7F-JF7|JL---7
-        7-L-JL7||F7|L7F-7F7|
-        L.L7LFJ|||||FJL7||LJ
-        L7JLJL-JLJLJL--JLJ.L
-    "};
-
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT_1)?,
-            "4".to_string()
+            solve_1(EXAMPLE_INPUT_1)?.value,
+            4.into()
         );
         assert_eq!(
-            solve_1(EXAMPLE_INPUT_2)?,
-            "8".to_string()
+            solve_1(EXAMPLE_INPUT_2)?.value,
+            8.into()
         );
         Ok(())
     }
@@ -310,31 +247,31 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_1(&input)?);
+        util::check_answer(day, Part::One, &solve_1(&input)?)?;
         Ok(())
     }
 
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_2(EXAMPLE_INPUT_1)?,
-            "1".to_string()
+            solve_2(EXAMPLE_INPUT_1)?.value,
+            1.into()
         );
         assert_eq!(
-            solve_2(EXAMPLE_INPUT_2)?,
-            "1".to_string()
+            solve_2(EXAMPLE_INPUT_2)?.value,
+            1.into()
         );
         assert_eq!(
-            solve_2(EXAMPLE_INPUT_3)?,
-            "4".to_string()
+            solve_2(EXAMPLE_INPUT_3)?.value,
+            4.into()
         );
         assert_eq!(
-            solve_2(EXAMPLE_INPUT_4)?,
-            "8".to_string()
+            solve_2(EXAMPLE_INPUT_4)?.value,
+            8.into()
         );
         assert_eq!(
-            solve_2(EXAMPLE_INPUT_5)?,
-            "10".to_string()
+            solve_2(EXAMPLE_INPUT_5)?.value,
+            10.into()
         );
         Ok(())
     }
@@ -348,7 +285,26 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_2(&input)?);
+        util::check_answer(day, Part::Two, &solve_2(&input)?)?;
         Ok(())
     }
+
+    #[test]
+    fn test_broken_pipe() {
+        let input = indoc!{"
+            .....
+            .S-7.
+            .|.|.
+            .L-..
+            .....
+        "};
+        assert_eq!(solve_1(input).unwrap_err().to_string(), "Pipe is broken");
+
+        let input = indoc!{"
+            S-7
+            |.|
+            |.|
+        "};
+        assert_eq!(solve_1(input).unwrap_err().to_string(), "Pipe leads out of the area");
+    }
 }
@@ -1,3 +1,9 @@
+use indoc::indoc;
+
+use crate::grid::Grid;
+use crate::point::Point;
+use crate::registry::Example;
+
 pub fn solve_1(input: &str) -> crate::PuzzleResult {
     solve_with_expansion_rate(input, 2)
 }
@@ -7,95 +13,73 @@ pub fn solve_2(input: &str) -> crate::PuzzleResult {
 }
 
 pub fn solve_with_expansion_rate(input: &str, expansion_rate: usize) -> crate::PuzzleResult {
-    let mut galaxies = parse(input);
-    expand(&mut galaxies, expansion_rate);
-    Ok(calc_total_dist(&galaxies).to_string())
-}
-
-fn parse(input: &str) -> Vec<(usize, usize)> {
-    let input = input.trim();
-    let mut galaxies = vec!();
-    let mut row_ix = 0;
-    let mut col_ix = 0;
-    for c in input.chars() {
-        if c == '\n' {
-            row_ix += 1;
-            col_ix = 0;
-            continue;
-        }
-        if c == '#' {
-            galaxies.push((row_ix, col_ix));
-        }
-
-        col_ix += 1;
-    }
-
-    galaxies
+    let image = Grid::parse(input)?;
+    let galaxies = expand(&image, expansion_rate);
+    Ok(calc_total_dist(&galaxies).into())
 }
 
-fn expand(
-    galaxies: &mut Vec<(usize, usize)>,
-    expansion_rate: usize,
-) {
-    galaxies.sort_by_key(|g| g.0);
-    let mut expansion = 0;
-    let mut last_row_ix = 0;
-    for galaxy in galaxies.iter_mut() {
-        if galaxy.0 - last_row_ix > 1 {
-            expansion += (galaxy.0 - last_row_ix - 1) * (expansion_rate - 1);
-        }
-        last_row_ix = galaxy.0;
-        galaxy.0 += expansion;
-    }
-
-    galaxies.sort_by_key(|g| g.1);
-    expansion = 0;
-    let mut last_col_ix = 0;
-    for galaxy in galaxies.iter_mut() {
-        if galaxy.1 - last_col_ix > 1 {
-            expansion += (galaxy.1 - last_col_ix - 1) * (expansion_rate - 1);
-        }
-        last_col_ix = galaxy.1;
-        galaxy.1 += expansion;
-    }
+/// Returns galaxy locations after every empty row and column grows `expansion_rate` times
+fn expand(image: &Grid<char>, expansion_rate: usize) -> Vec<Point> {
+    let is_empty = |c: &char| *c == '.';
+    let grow = |is_empty_line: Vec<bool>| {
+        is_empty_line.iter()
+            .scan(0, |shift, &is_empty| {
+                let ix = *shift;
+                if is_empty {
+                    *shift += expansion_rate - 1;
+                }
+                Some(ix)
+            })
+            .collect::<Vec<_>>()
+    };
+    let row_shifts = grow(image.rows().map(|row| row.iter().all(is_empty)).collect());
+    let col_shifts = grow((0..image.width()).map(|col| image.col(col).all(is_empty)).collect());
+
+    image.iter()
+        .filter(|(_, &c)| c == '#')
+        .map(|(p, _)| Point::new(p.row + row_shifts[p.row], p.col + col_shifts[p.col]))
+        .collect()
 }
 
-fn calc_total_dist(galaxies: &Vec<(usize, usize)>) -> u64 {
+fn calc_total_dist(galaxies: &[Point]) -> u64 {
     let mut total_dist = 0u64;
     for (i, galaxy) in galaxies.iter().enumerate() {
         for neighbor_galaxy in &galaxies[i..] {
-            total_dist += (galaxy.0 as i64 - neighbor_galaxy.0 as i64).abs() as u64;
-            total_dist += (galaxy.1 as i64 - neighbor_galaxy.1 as i64).abs() as u64;
+            total_dist += galaxy.manhattan_distance(*neighbor_galaxy) as u64;
         }
     }
     total_dist
 }
 
+const EXAMPLE_INPUT: &str = indoc!{"
+    ...#......
+    .......#..
+    #.........
+    ..........
+    ......#...
+    .#........
+    .........#
+    ..........
+    .......#..
+    #...#.....
+"};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT, Some(374), None),
+];
+
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use test_log::test;
+    use crate::registry::Part;
     use crate::util;
     use super::*;
 
-    const EXAMPLE_INPUT: &'static str = indoc!{"
-        ...#......
-        .......#..
-        #.........
-        ..........
-        ......#...
-        .#........
-        .........#
-        ..........
-        .......#..
-        #...#.....
-    "};
-
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT)?,
-            "374".to_string()
+            solve_1(EXAMPLE_INPUT)?.value,
+            374.into()
         );
         Ok(())
     }
@@ -109,19 +93,19 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_1(&input)?);
+        util::check_answer(day, Part::One, &solve_1(&input)?)?;
         Ok(())
     }
 
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_with_expansion_rate(EXAMPLE_INPUT, 10)?,
-            "1030".to_string()
+            solve_with_expansion_rate(EXAMPLE_INPUT, 10)?.value,
+            1030.into()
         );
         assert_eq!(
-            solve_with_expansion_rate(EXAMPLE_INPUT, 100)?,
-            "8410".to_string()
+            solve_with_expansion_rate(EXAMPLE_INPUT, 100)?.value,
+            8410.into()
         );
         Ok(())
     }
@@ -135,7 +119,7 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_2(&input)?);
+        util::check_answer(day, Part::Two, &solve_2(&input)?)?;
         Ok(())
     }
 }
@@ -1,39 +1,151 @@
+use indoc::indoc;
+
+use crate::parse_error::{self, Line, ParseError};
+use crate::registry::Example;
+
+const NUM_FOLDS: usize = 5;
+
 pub fn solve_1(input: &str) -> crate::PuzzleResult {
-    todo!()
+    let records = parse(input)?;
+
+    let total = records.iter()
+        .map(count_arrangements)
+        .sum::<u64>();
+    Ok(total.into())
 }
 
 pub fn solve_2(input: &str) -> crate::PuzzleResult {
-    todo!()
+    let records = parse(input)?;
+
+    let total = records.iter()
+        .map(|record| count_arrangements(&record.unfold(NUM_FOLDS)))
+        .sum::<u64>();
+    Ok(total.into())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Spring {
+    Operational,
+    Damaged,
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Record {
+    pub springs: Vec<Spring>,
+    /// Sizes of contiguous groups of damaged springs
+    pub groups: Vec<usize>,
+}
+
+impl Record {
+    fn unfold(&self, num_folds: usize) -> Self {
+        let mut springs = self.springs.clone();
+        for _ in 1..num_folds {
+            springs.push(Spring::Unknown);
+            springs.extend(&self.springs);
+        }
+        Self {
+            springs,
+            groups: self.groups.repeat(num_folds),
+        }
+    }
 }
 
-fn parse(input: &str) -> anyhow::Result<()> {
-    let mut lines = input.lines();
-    for line in lines {
-        let line = line.trim();
-        if line.is_empty() {
+/// Counts arrangements of damaged springs from the end of the record.
+/// `counts[i][j]` is the number of ways to place groups `j..` into springs `i..`
+fn count_arrangements(record: &Record) -> u64 {
+    let springs = &record.springs;
+    let groups = &record.groups;
+    let num_springs = springs.len();
+
+    let mut counts = vec!(vec!(0u64; groups.len() + 1); num_springs + 2);
+    counts[num_springs][groups.len()] = 1;
+    counts[num_springs + 1][groups.len()] = 1;
+
+    for spring_ix in (0..num_springs).rev() {
+        for group_ix in 0..=groups.len() {
+            let mut count = 0;
+            if springs[spring_ix] != Spring::Damaged {
+                count += counts[spring_ix + 1][group_ix];
+            }
+            if springs[spring_ix] != Spring::Operational && group_ix < groups.len() {
+                let group_end = spring_ix + groups[group_ix];
+                let fits = group_end <= num_springs
+                    && springs[spring_ix..group_end].iter().all(|&s| s != Spring::Operational)
+                    && springs.get(group_end) != Some(&Spring::Damaged);
+                if fits {
+                    // Skips the group and the operational spring after it
+                    count += counts[group_end + 1][group_ix + 1];
+                }
+            }
+            counts[spring_ix][group_ix] = count;
+        }
+    }
+
+    counts[0][0]
+}
+
+fn parse(input: &str) -> Result<Vec<Record>, ParseError> {
+    let mut records = vec!();
+    for line in parse_error::lines(input) {
+        let text = line.text.trim();
+        if text.is_empty() {
             continue;
         }
+        records.push(parse_record(&line, text)?);
     }
 
-    todo!()
+    Ok(records)
+}
+
+fn parse_record(line: &Line, text: &str) -> Result<Record, ParseError> {
+    let (springs_str, groups_str) = text.split_once(' ')
+        .ok_or_else(|| line.error(text, "Expect springs and group sizes"))?;
+
+    let springs = springs_str.char_indices()
+        .map(|(ix, c)| {
+            match c {
+                '.' => Ok(Spring::Operational),
+                '#' => Ok(Spring::Damaged),
+                '?' => Ok(Spring::Unknown),
+                _ => Err(line.error(&springs_str[ix..], format!("Unknown spring '{c}'"))),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let groups = groups_str.trim()
+        .split(',')
+        .map(|v| v.parse().map_err(|_| line.error(v, "Expect group size")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Record { springs, groups })
 }
 
+const EXAMPLE_INPUT: &str = indoc!{"
+    ???.### 1,1,3
+    .??..??...?##. 1,1,3
+    ?#?#?#?#?#?#?#? 1,3,1,6
+    ????.#...#... 4,1,1
+    ????.######..#####. 1,6,5
+    ?###???????? 3,2,1
+"};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT, Some(21), Some(525152)),
+];
 
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use test_log::test;
+    use crate::registry::Part;
     use crate::util;
     use super::*;
 
-    const EXAMPLE_INPUT: &'static str = indoc!{"
-    "};
-
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT)?,
-            "".to_string()
+            solve_1(EXAMPLE_INPUT)?.value,
+            21.into()
         );
         Ok(())
     }
@@ -47,15 +159,15 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_1(&input)?);
+        util::check_answer(day, Part::One, &solve_1(&input)?)?;
         Ok(())
     }
 
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_2(EXAMPLE_INPUT)?,
-            "".to_string()
+            solve_2(EXAMPLE_INPUT)?.value,
+            525152.into()
         );
         Ok(())
     }
@@ -69,7 +181,29 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_2(&input)?);
+        util::check_answer(day, Part::Two, &solve_2(&input)?)?;
         Ok(())
     }
+
+    #[test]
+    fn test_count_arrangements() -> anyhow::Result<()> {
+        let records = parse(EXAMPLE_INPUT)?;
+        assert_eq!(
+            records.iter().map(count_arrangements).collect::<Vec<_>>(),
+            vec!(1, 4, 1, 1, 4, 10)
+        );
+        assert_eq!(
+            records.iter().map(|r| count_arrangements(&r.unfold(NUM_FOLDS))).collect::<Vec<_>>(),
+            vec!(1, 16384, 1, 16, 2500, 506250)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("???.### 1,1,3\n?x? 1\n").unwrap_err();
+        assert_eq!(err.to_string(), "Unknown spring 'x' at line 2, column 2");
+        let err = parse("???.### 1,a,3\n").unwrap_err();
+        assert_eq!(err.to_string(), "Expect group size at line 1, column 11");
+    }
 }
@@ -1,25 +1,27 @@
+use indoc::indoc;
+
+use crate::grid::Grid;
+use crate::registry::Example;
+
 pub fn solve_1(input: &str) -> crate::PuzzleResult {
     let mirrors = parse(input)?;
-    Ok(solve(&mirrors, 0).to_string())
+    Ok(solve(&mirrors, 0).into())
 }
 
 pub fn solve_2(input: &str) -> crate::PuzzleResult {
     let mirrors = parse(input)?;
-    Ok(solve(&mirrors, 1).to_string())
+    Ok(solve(&mirrors, 1).into())
 }
 
-fn solve(mirrors: &[Mirror], num_smudges: u32) -> u32 {
-    // dbg!(&mirrors);
+/// Cell is `true` for a rock
+type Mirror = Grid<bool>;
 
+fn solve(mirrors: &[Mirror], num_smudges: usize) -> u32 {
     let mut score = 0;
     for mirror in mirrors.iter() {
-        let horizontal_split_ix = find_perfect_reflection(&mirror.rows, num_smudges);
-        let vertical_split_ix = find_perfect_reflection(&mirror.cols, num_smudges);
+        let horizontal_split_ix = find_perfect_reflection(mirror, num_smudges);
+        let vertical_split_ix = find_perfect_reflection(&mirror.transpose(), num_smudges);
 
-        // assert!(!(horizontal_split_ix != 0 && vertical_split_ix != 0));
-
-        // dbg!(horizontal_split_ix);
-        // dbg!(vertical_split_ix);
         score += horizontal_split_ix * 100;
         score += vertical_split_ix;
     }
@@ -27,12 +29,12 @@ fn solve(mirrors: &[Mirror], num_smudges: u32) -> u32 {
     score as u32
 }
 
-fn find_perfect_reflection(rows: &[u64], num_smudges: u32) -> usize {
+/// Looks for a horizontal line of reflection, returns the number of rows above it
+fn find_perfect_reflection(mirror: &Mirror, num_smudges: usize) -> usize {
+    let rows = mirror.rows().collect::<Vec<_>>();
     let mut reflection_ix = 0;
     let half_mirror_height = rows.len() / 2;
     for reflection_len in (1..half_mirror_height + 1).rev() {
-        // dbg!(reflection_len);
-
         let top_split_ix = reflection_len;
         let is_top_matched = is_reflection(
             &rows[0..top_split_ix],
@@ -58,136 +60,110 @@ fn find_perfect_reflection(rows: &[u64], num_smudges: u32) -> usize {
 }
 
 fn is_reflection(
-    part1: &[u64],
-    part2: &[u64],
-    num_smudges: u32,
+    part1: &[&[bool]],
+    part2: &[&[bool]],
+    num_smudges: usize,
 ) -> bool {
     part1.iter().zip(part2.iter().rev())
-        .map(|(n1, n2)| n1 ^ n2)
-        .map(u64::count_ones)
-        .sum::<u32>() == num_smudges
-}
-
-#[derive(Debug, Default)]
-struct Mirror {
-    pub rows: Vec<u64>,
-    pub cols: Vec<u64>,
+        .map(|(row1, row2)| {
+            row1.iter().zip(row2.iter())
+                .filter(|(c1, c2)| c1 != c2)
+                .count()
+        })
+        .sum::<usize>() == num_smudges
 }
 
 fn parse(input: &str) -> anyhow::Result<Vec<Mirror>> {
-    let lines = input.lines();
-    let mut mirrors = vec!();
-    let mut cur_rows = vec!();
-    let mut cur_cols = vec!();
-    for line in lines {
-        let line = line.trim();
-        if line.is_empty() {
-            if !cur_rows.is_empty() && !cur_cols.is_empty() {
-                mirrors.push(Mirror { rows: cur_rows, cols: cur_cols });
-                cur_rows = vec!();
-                cur_cols = vec!();
-            }
-            continue;
+    let mirrors = Grid::parse_many_with(input, |c| {
+        match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
         }
-
-        let mut row = 0;
-        for (col_ix, c) in line.chars().enumerate() {
-            if cur_rows.is_empty() {
-                cur_cols.resize_with(line.len(), || 0);
-            }
-            row <<= 1;
-            if let Some(col) = cur_cols.get_mut(col_ix) {
-                *col <<= 1;
-            }
-            if c == '#' {
-                row |= 1;
-                if let Some(col) = cur_cols.get_mut(col_ix) {
-                    *col |= 1;
-                }
-            }
-        }
-        cur_rows.push(row);
-    }
-    if !cur_rows.is_empty() && !cur_cols.is_empty() {
-        mirrors.push(Mirror { rows: cur_rows, cols: cur_cols });
-    }
-
+    })?;
     Ok(mirrors)
 }
 
+const EXAMPLE_INPUT_1: &str = indoc!{"
+    #.##..##.
+    ..#.##.#.
+    ##......#
+    ##......#
+    ..#.##.#.
+    ..##..##.
+    #.#.##.#.
+
+    #...##..#
+    #....#..#
+    ..##..###
+    #####.##.
+    #####.##.
+    ..##..###
+    #....#..#
+"};
+const EXAMPLE_INPUT_2: &str = indoc!{"
+    ##..##..##.
+    ######..###
+    .####.##.##
+    ..........#
+    .####.##.##
+    .####....##
+    ..##..##..#
+"};
+const EXAMPLE_INPUT_3: &str = indoc!{"
+    ####...
+    ##.#...
+    ####...
+    .#.#...
+    ##.####
+    ##.##..
+    #####..
+    ##.#.##
+    .##....
+    .##.###
+    ##..#..
+    #..####
+    #...#..
+"};
+const EXAMPLE_INPUT_4: &str = indoc!{"
+    ..##..###
+    #####.##.
+    #####.##.
+    ..##..###
+    #..#.#..#
+"};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT_1, Some(405), Some(400)),
+    Example::new(EXAMPLE_INPUT_2, Some(3), None),
+    Example::new(EXAMPLE_INPUT_3, Some(6), None),
+    Example::new(EXAMPLE_INPUT_4, Some(200), None),
+];
+
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use test_log::test;
+    use crate::registry::Part;
     use crate::util;
     use super::*;
 
-    const EXAMPLE_INPUT_1: &'static str = indoc!{"
-        #.##..##.
-        ..#.##.#.
-        ##......#
-        ##......#
-        ..#.##.#.
-        ..##..##.
-        #.#.##.#.
-
-        #...##..#
-        #....#..#
-        ..##..###
-        #####.##.
-        #####.##.
-        ..##..###
-        #....#..#
-    "};
-    const EXAMPLE_INPUT_2: &'static str = indoc!{"
-        ##..##..##.
-        ######..###
-        .####.##.##
-        ..........#
-        .####.##.##
-        .####....##
-        ..##..##..#
-    "};
-    const EXAMPLE_INPUT_3: &'static str = indoc!{"
-        ####...
-        ##.#...
-        ####...
-        .#.#...
-        ##.####
-        ##.##..
-        #####..
-        ##.#.##
-        .##....
-        .##.###
-        ##..#..
-        #..####
-        #...#..
-    "};
-    const EXAMPLE_INPUT_4: &'static str = indoc!{"
-        ..##..###
-        #####.##.
-        #####.##.
-        ..##..###
-        #..#.#..#
-    "};
-
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT_1)?,
-            "405".to_string()
+            solve_1(EXAMPLE_INPUT_1)?.value,
+            405.into()
         );
         assert_eq!(
-            solve_1(EXAMPLE_INPUT_2)?,
-            "3".to_string()
+            solve_1(EXAMPLE_INPUT_2)?.value,
+            3.into()
         );
         assert_eq!(
-            solve_1(EXAMPLE_INPUT_3)?,
-            "6".to_string()
+            solve_1(EXAMPLE_INPUT_3)?.value,
+            6.into()
         );
         assert_eq!(
-            solve_1(EXAMPLE_INPUT_4)?,
-            "200".to_string()
+            solve_1(EXAMPLE_INPUT_4)?.value,
+            200.into()
         );
         Ok(())
     }
@@ -201,15 +177,15 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_1(&input)?);
+        util::check_answer(day, Part::One, &solve_1(&input)?)?;
         Ok(())
     }
 
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_2(EXAMPLE_INPUT_1)?,
-            "400".to_string()
+            solve_2(EXAMPLE_INPUT_1)?.value,
+            400.into()
         );
         Ok(())
     }
@@ -223,7 +199,7 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_2(&input)?);
+        util::check_answer(day, Part::Two, &solve_2(&input)?)?;
         Ok(())
     }
 }
@@ -1,4 +1,10 @@
-use std::{mem::swap, collections::HashMap};
+use indoc::indoc;
+
+use crate::cycle;
+use crate::grid::Grid;
+use crate::point::Point;
+use crate::registry::Example;
+use crate::solution::Solution;
 
 use sha1::{
     Sha1,
@@ -11,191 +17,116 @@ use sha1::{
 
 pub fn solve_1(input: &str) -> crate::PuzzleResult {
     let mut platform = parse(input)?;
-    // dbg!(&platform);
 
-    while shift_north(&mut platform) != 0 {}
+    tilt_north(&mut platform);
 
-    Ok(calc_weight(&platform).to_string())
+    Ok(calc_weight(&platform).into())
 }
 
 pub fn solve_2(input: &str) -> crate::PuzzleResult {
-    let mut platform = parse(input)?;
-
-    let mut seen_states = HashMap::new();
-    let mut weights = vec!();
-    let mut cur_ix = 0usize;
-    let (offset_to_repeat, cycle_len) = loop {
-        while shift_north(&mut platform) != 0 {}
-        while shift_west(&mut platform) != 0 {}
-        while shift_south(&mut platform) != 0 {}
-        while shift_east(&mut platform) != 0 {}
-
-        let hash = calc_hash(&platform);
-        if let Some(prev_ix) = seen_states.get(&hash) {
-            break (prev_ix, cur_ix - prev_ix);
-        }
-        let weight = calc_weight(&platform);
-        weights.push(weight);
-        seen_states.insert(hash, cur_ix);
-        cur_ix += 1;
-    };
-
-    if cycle_len == 0 {
-        anyhow::bail!("Invalid cycle length value")
-    }
+    let platform = parse(input)?;
 
-    let weight_ix = (1_000_000_000 - offset_to_repeat) % cycle_len;
-    Ok(weights[offset_to_repeat + weight_ix - 1].to_string())
-}
-
-fn parse(input: &str) -> anyhow::Result<Vec<Vec<char>>> {
-    let lines = input.lines();
-    let mut records = vec!();
-    for line in lines {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
+    let (platform, cycle) = cycle::run(platform, 1_000_000_000, calc_hash, spin_cycle);
 
-        records.push(line.chars().collect());
+    let mut solution = Solution::from(calc_weight(&platform));
+    if let Some(cycle) = cycle {
+        solution = solution
+            .with_diagnostic("Cycle offset", cycle.offset)
+            .with_diagnostic("Cycle length", cycle.len);
     }
+    Ok(solution)
+}
 
-    Ok(records)
+fn parse(input: &str) -> anyhow::Result<Grid<char>> {
+    Ok(Grid::parse_with(input, |c| matches!(c, 'O' | '#' | '.').then_some(c))?)
 }
 
-fn calc_hash(platform: &[Vec<char>]) -> GenericArray<u8, U20> {
+/// Tiles fit into a byte, `parse` rejects anything else
+fn calc_hash(platform: &Grid<char>) -> GenericArray<u8, U20> {
     let mut hasher = Sha1::new();
     let mut buf = [0; 1];
-    for row in platform {
-        for c in row {
-            c.encode_utf8(&mut buf);
-            hasher.update(&buf);
-        }
+    for c in platform.values() {
+        c.encode_utf8(&mut buf);
+        hasher.update(&buf);
     }
     hasher.finalize()
 }
 
-fn print_platform(platform: &[Vec<char>]) {
-    for row in platform {
-        println!(
-            "{}",
-            row.iter().collect::<String>()
-        );
-    }
-}
-
-fn shift_north(platform: &mut [Vec<char>]) -> usize {
-    let mut num_shifted = 0;
-    for i in 1..platform.len() {
-        let (top, bottom) = platform.split_at_mut(i);
-        for (src, dst) in bottom[0].iter_mut().zip(top.last_mut().unwrap()) {
-            match (&src, &dst) {
-                ('O', '.') => {
-                    swap(src, dst);
-                    num_shifted += 1;
-                }
-                _ => {}
-            }
-        }
-    }
-
-    num_shifted
+fn print_platform(platform: &Grid<char>) {
+    print!("{platform}");
 }
 
-fn shift_south(platform: &mut [Vec<char>]) -> usize {
-    let mut num_shifted = 0;
-    for i in (1..platform.len()).rev() {
-        let (top, bottom) = platform.split_at_mut(i);
-        for (src, dst) in top.last_mut().unwrap().iter_mut().zip(bottom[0].iter_mut()) {
-            match (&src, &dst) {
-                ('O', '.') => {
-                    swap(src, dst);
-                    num_shifted += 1;
-                }
-                _ => {}
-            }
-        }
+/// Tilts north, west, south and east. Rotating clockwise after every tilt
+/// brings the next side to the north and restores the orientation at the end
+fn spin_cycle(platform: &Grid<char>) -> Grid<char> {
+    let mut platform = platform.clone();
+    for _ in 0..4 {
+        tilt_north(&mut platform);
+        platform = platform.rotate_cw();
     }
-
-    num_shifted
+    platform
 }
 
-fn shift_west(platform: &mut [Vec<char>]) -> usize {
-    let mut num_shifted = 0;
-    for src_col_ix in 1..platform[0].len() {
-        for row_ix in 0..platform.len() {
-            let (left, right) = platform[row_ix].split_at_mut(src_col_ix);
-            let dst = left.last_mut().unwrap();
-            let src = right.get_mut(0).unwrap();
-            match (&src, &dst) {
-                ('O', '.') => {
-                    swap(src, dst);
-                    num_shifted += 1;
+fn tilt_north(platform: &mut Grid<char>) {
+    for col_ix in 0..platform.width() {
+        let mut free_row_ix = 0;
+        for row_ix in 0..platform.height() {
+            let loc = Point::new(row_ix, col_ix);
+            match platform[loc] {
+                '#' => {
+                    free_row_ix = row_ix + 1;
                 }
-                _ => {}
-            }
-        }
-    }
-
-    num_shifted
-}
-
-fn shift_east(platform: &mut [Vec<char>]) -> usize {
-    let mut num_shifted = 0;
-    for src_col_ix in 1..platform[0].len() {
-        for row_ix in 0..platform.len() {
-            let (left, right) = platform[row_ix].split_at_mut(src_col_ix);
-            let src = left.last_mut().unwrap();
-            let dst = right.get_mut(0).unwrap();
-            match (&src, &dst) {
-                ('O', '.') => {
-                    swap(src, dst);
-                    num_shifted += 1;
+                'O' => {
+                    platform[loc] = '.';
+                    platform[Point::new(free_row_ix, col_ix)] = 'O';
+                    free_row_ix += 1;
                 }
                 _ => {}
             }
         }
     }
-
-    num_shifted
 }
 
-fn calc_weight(platform: &[Vec<char>]) -> usize {
-    platform.iter().enumerate()
+fn calc_weight(platform: &Grid<char>) -> usize {
+    platform.rows().enumerate()
         .map(|(i, row)| {
             let w = row.iter()
                 .map(|&c| (c == 'O') as usize)
                 .sum::<usize>();
-            (platform.len() - i) * w
+            (platform.height() - i) * w
         })
         .sum()
 }
 
+const EXAMPLE_INPUT: &str = indoc!{"
+    O....#....
+    O.OO#....#
+    .....##...
+    OO.#O....O
+    .O.....O#.
+    O.#..O.#.#
+    ..O..#O..O
+    .......O..
+    #....###..
+    #OO..#....
+"};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT, Some(136), Some(64)),
+];
+
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use test_log::test;
+    use crate::registry::Part;
     use crate::util;
     use super::*;
 
-    const EXAMPLE_INPUT: &'static str = indoc!{"
-        O....#....
-        O.OO#....#
-        .....##...
-        OO.#O....O
-        .O.....O#.
-        O.#..O.#.#
-        ..O..#O..O
-        .......O..
-        #....###..
-        #OO..#....
-    "};
-
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT)?,
-            "136".to_string()
+            solve_1(EXAMPLE_INPUT)?.value,
+            136.into()
         );
         Ok(())
     }
@@ -209,15 +140,15 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_1(&input)?);
+        util::check_answer(day, Part::One, &solve_1(&input)?)?;
         Ok(())
     }
 
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_2(EXAMPLE_INPUT)?,
-            "64".to_string()
+            solve_2(EXAMPLE_INPUT)?.value,
+            64.into()
         );
         Ok(())
     }
@@ -231,7 +162,13 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_2(&input)?);
+        util::check_answer(day, Part::Two, &solve_2(&input)?)?;
         Ok(())
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = solve_2("O.#\n.é.\n").unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected character 'é' at line 2, column 2");
+    }
 }
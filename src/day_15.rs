@@ -1,11 +1,16 @@
 use arr_macro::arr;
 
+use indoc::indoc;
+
+use crate::parse_error::{self, ParseError};
+use crate::registry::Example;
+
 pub fn solve_1(input: &str) -> crate::PuzzleResult {
     let res = parse_1(input)?.iter()
         .map(|h| calc_hash(h))
         .sum::<usize>();
 
-    Ok(res.to_string())
+    Ok(res.into())
 }
 
 pub fn solve_2(input: &str) -> crate::PuzzleResult {
@@ -50,33 +55,31 @@ pub fn solve_2(input: &str) -> crate::PuzzleResult {
         })
         .sum::<usize>();
 
-    Ok(focus_power.to_string())
+    Ok(focus_power.into())
 }
 
 fn parse_1(input: &str) -> anyhow::Result<Vec<&str>> {
     Ok(input.trim().split(',').collect())
 }
 
-fn parse_2(input: &str) -> anyhow::Result<Vec<Operation<'_>>> {
-    input.trim()
-         .split(',')
-         .filter_map(|s| {
-             if let Some((label, focal_len_str)) = s.split_once(|c| c == '=' || c == '-') {
-                 let op_res = if focal_len_str.is_empty() {
-                     Ok(Operation::Remove { label })
-                 } else {
-                     match focal_len_str.parse() {
-                         Ok(focal_len) => Ok(Operation::Add { lens: Lens { label, focal_len } }),
-                         Err(e) => Err(anyhow::anyhow!("Focal length must be an integer number: {e}")),
-                     }
-
-                 };
-                 Some(op_res)
-             } else {
-                 None
-             }
-         })
-         .collect()
+/// Steps are separated by commas, newlines are ignored
+fn parse_2(input: &str) -> Result<Vec<Operation<'_>>, ParseError> {
+    let mut ops = vec!();
+    for line in parse_error::lines(input) {
+        for step in line.text.trim().split(',').filter(|s| !s.is_empty()) {
+            let op = if let Some(label) = step.strip_suffix('-') {
+                Operation::Remove { label }
+            } else if let Some((label, focal_len_str)) = step.split_once('=') {
+                let focal_len = focal_len_str.parse()
+                    .map_err(|_| line.error(focal_len_str, "Focal length must be an integer number"))?;
+                Operation::Add { lens: Lens { label, focal_len } }
+            } else {
+                return Err(line.error(step, "Expect '=' or '-' operation"));
+            };
+            ops.push(op);
+        }
+    }
+    Ok(ops)
 }
 
 fn calc_hash(h: &str) -> usize {
@@ -98,22 +101,26 @@ enum Operation<'a> {
     Remove { label: &'a str },
 }
 
+const EXAMPLE_INPUT: &str = indoc!{"
+    rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7
+"};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT, Some(1320), Some(145)),
+];
+
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use test_log::test;
+    use crate::registry::Part;
     use crate::util;
     use super::*;
 
-    const EXAMPLE_INPUT: &'static str = indoc!{"
-        rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7
-    "};
-
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT)?,
-            "1320".to_string()
+            solve_1(EXAMPLE_INPUT)?.value,
+            1320.into()
         );
         Ok(())
     }
@@ -127,15 +134,15 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_1(&input)?);
+        util::check_answer(day, Part::One, &solve_1(&input)?)?;
         Ok(())
     }
 
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_2(EXAMPLE_INPUT)?,
-            "145".to_string()
+            solve_2(EXAMPLE_INPUT)?.value,
+            145.into()
         );
         Ok(())
     }
@@ -149,7 +156,16 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_2(&input)?);
+        util::check_answer(day, Part::Two, &solve_2(&input)?)?;
         Ok(())
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = solve_2("rn=1,cm-,qp=x").unwrap_err();
+        assert_eq!(err.to_string(), "Focal length must be an integer number at line 1, column 13");
+
+        let err = solve_2("rn=1,cm,qp=3").unwrap_err();
+        assert_eq!(err.to_string(), "Expect '=' or '-' operation at line 1, column 6");
+    }
 }
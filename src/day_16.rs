@@ -1,19 +1,28 @@
 use std::collections::HashSet;
 
+use indoc::indoc;
+
+use crate::grid::Grid;
+use crate::point::{Direction, Point};
+use crate::registry::Example;
+use crate::solution::Solution;
+
 pub fn solve_1(input: &str) -> crate::PuzzleResult {
     let mut grid = parse(input)?;
     // dbg!(&grid);
 
     let start_beam = Beam {
-        loc: Location { row: 0, col: 0 },
+        loc: Point { row: 0, col: 0 },
         dir: Direction::Right,
     };
     trace_grid(&mut grid, start_beam);
 
-    Ok(grid.count_energized_tiles().to_string())
+    let solution = Solution::from(count_energized_tiles(&grid))
+        .with_visualization(render_energized_tiles(&grid));
+    Ok(solution)
 }
 
-fn trace_grid(grid: &mut Grid, start_beam: Beam) {
+fn trace_grid(grid: &mut Grid<Tile>, start_beam: Beam) {
     let mut beams = vec!();
     beams.push(start_beam);
 
@@ -41,23 +50,23 @@ pub fn solve_2(input: &str) -> crate::PuzzleResult {
     let initial_grid = parse(input)?;
 
     let mut start_beams = vec!();
-    for row in 0..initial_grid.len() {
+    for row in 0..initial_grid.height() {
         start_beams.push(Beam {
-            loc: Location { row, col: 0 },
+            loc: Point { row, col: 0 },
             dir: Direction::Right,
         });
         start_beams.push(Beam {
-            loc: Location { row, col: initial_grid[0].len() - 1 },
+            loc: Point { row, col: initial_grid.width() - 1 },
             dir: Direction::Left,
         });
     }
-    for col in 0..initial_grid[0].len() {
+    for col in 0..initial_grid.width() {
         start_beams.push(Beam {
-            loc: Location { row: 0, col },
+            loc: Point { row: 0, col },
             dir: Direction::Down,
         });
         start_beams.push(Beam {
-            loc: Location { row: initial_grid.len() - 1, col },
+            loc: Point { row: initial_grid.height() - 1, col },
             dir: Direction::Up,
         });
     }
@@ -66,38 +75,26 @@ pub fn solve_2(input: &str) -> crate::PuzzleResult {
     for beam in start_beams {
         let mut grid = initial_grid.clone();
         trace_grid(&mut grid, beam);
-        let energized_tiles = grid.count_energized_tiles();
+        let energized_tiles = count_energized_tiles(&grid);
         if energized_tiles > max_energized_tiles {
             max_energized_tiles = energized_tiles;
         }
     }
 
-    Ok(max_energized_tiles.to_string())
+    Ok(max_energized_tiles.into())
 }
 
-fn parse(input: &str) -> anyhow::Result<Grid> {
-    let lines = input.lines();
-    let mut grid = vec!();
-    for line in lines {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-
-        let mut row = vec!();
-        for c in line.chars() {
-            let mirror = match c {
-                '/' => Some('/'),
-                '\\' => Some('\\'),
-                '|' => Some('|'),
-                '-' => Some('-'),
-                _ => None,
-            };
-            row.push(Tile { mirror, energized_by: HashSet::new() });
-        }
-        grid.push(row);
+fn parse(input: &str) -> anyhow::Result<Grid<Tile>> {
+    let grid = Grid::parse_with(input, |c| {
+        let mirror = match c {
+            '/' | '\\' | '|' | '-' => Some(c),
+            _ => None,
+        };
+        Some(Tile { mirror, energized_by: HashSet::new() })
+    })?;
+    if grid.width() == 0 {
+        anyhow::bail!("Expect contraption tiles");
     }
-
     Ok(grid)
 }
 
@@ -113,155 +110,61 @@ impl Tile {
     }
 }
 
-trait IGrid {
-    fn tile(&mut self, loc: &Location) -> Option<&mut Tile>;
-
-    fn energize(&mut self, beam: &Beam);
-
-    fn count_energized_tiles(&self) -> usize;
+fn count_energized_tiles(grid: &Grid<Tile>) -> usize {
+    grid.values()
+        .filter(|tile| !tile.energized_by.is_empty())
+        .count()
 }
 
-type Grid = Vec<Vec<Tile>>;
-
-impl IGrid for Grid {
-    fn tile(&mut self, loc: &Location) -> Option<&mut Tile> {
-        self.get_mut(loc.row).and_then(|row| row.get_mut(loc.col))
-    }
-
-    fn energize(&mut self, beam: &Beam) {
-        self.tile(&beam.loc).unwrap().energize(beam.dir);
-    }
-
-    fn count_energized_tiles(&self) -> usize {
-        let mut count = 0;
-        for row in self {
-            for tile in row {
-                if !tile.energized_by.is_empty() {
-                    count += 1;
-                }
-            }
-        }
-        count
-    }
-}
-
-#[derive(Debug, PartialEq, Eq)]
-struct Location {
-    pub row: usize,
-    pub col: usize,
-}
-
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+fn render_energized_tiles(grid: &Grid<Tile>) -> String {
+    grid.map(|tile| if tile.energized_by.is_empty() { '.' } else { '#' })
+        .to_string()
 }
 
 #[derive(Debug, PartialEq, Eq)]
 struct Beam {
-    pub loc: Location,
+    pub loc: Point,
     pub dir: Direction,
 }
 
 impl Beam {
-    fn tick(&mut self, grid: &mut Grid) -> Tick {
-        use Direction::*;
-
+    fn tick(&mut self, grid: &mut Grid<Tile>) -> Tick {
         // dbg!(&self.loc);
-        let tile = grid.tile(&self.loc).unwrap();
+        let tile = &mut grid[self.loc];
         if tile.energized_by.contains(&self.dir) {
             return Tick::EndOfLife;
         }
         tile.energize(self.dir);
 
-        let (new_dir, split_dir) = match (self.dir, tile.mirror) {
-            (Right, Some('/')) => {
-                (Up, None)
-            }
-            (Right, Some('\\')) => {
-                (Down, None)
-            }
-            (Left, Some('/')) => {
-                (Down, None)
-            }
-            (Left, Some('\\')) => {
-                (Up, None)
+        let (new_dir, split_dir) = match (tile.mirror, self.dir.is_vertical()) {
+            (Some('/'), false) | (Some('\\'), true) => {
+                (self.dir.turn_left(), None)
             }
-            (Right | Left, Some('|')) if self.loc.row == 0 => {
-                (Down, None)
+            (Some('/'), true) | (Some('\\'), false) => {
+                (self.dir.turn_right(), None)
             }
-            (Right | Left, Some('|')) if self.loc.row == grid.len() - 1 => {
-                (Up, None)
-            }
-            (Right | Left, Some('|')) => {
-                (Up, Some(Down))
-            }
-            (Up, Some('/')) => {
-                (Right, None)
-            }
-            (Up, Some('\\')) => {
-                (Left, None)
-            }
-            (Down, Some('/')) => {
-                (Left, None)
-            }
-            (Down, Some('\\')) => {
-                (Right, None)
-            }
-            (Up | Down, Some('-')) if self.loc.col == 0 => {
-                (Right, None)
-            }
-            (Up | Down, Some('-')) if self.loc.col == grid[0].len() - 1 => {
-                (Left, None)
-            }
-            (Up | Down, Some('-')) => {
-                (Left, Some(Right))
+            (Some('|'), false) | (Some('-'), true) => {
+                (self.dir.turn_left(), Some(self.dir.turn_right()))
             }
             _ => {
                 (self.dir, None)
             }
         };
 
-        self.dir = new_dir;
-
-        let tick_res = if let Some(split_dir) = split_dir {
-            let mut split_beam = Beam {
-                loc: Location { row: self.loc.row, col: self.loc.col },
-                dir: split_dir,
-            };
-            split_beam.move_toward(grid);
-            Tick::Split { new: split_beam }
-        } else {
-            Tick::Continue
-        };
-
-        if !self.move_toward(grid) {
-            return Tick::EndOfLife;
-        }
-        tick_res
-    }
-
-    fn move_toward(&mut self, grid: &Grid) -> bool {
-        use Direction::*;
-
-        match self.dir {
-            Up if self.loc.row != 0 => {
-                self.loc.row -= 1;
-            }
-            Down if self.loc.row != grid.len() - 1 => {
-                self.loc.row += 1;
-            }
-            Left if self.loc.col != 0 => {
-                self.loc.col -= 1;
-            }
-            Right if self.loc.col != grid[0].len() - 1 => {
-                self.loc.col += 1;
+        // Beams that leave the grid are gone
+        let mut next_beams = [Some(new_dir), split_dir].into_iter()
+            .flatten()
+            .filter_map(|dir| Some(Beam { loc: grid.step(self.loc, dir)?, dir }));
+        match (next_beams.next(), next_beams.next()) {
+            (Some(next), split) => {
+                *self = next;
+                match split {
+                    Some(new) => Tick::Split { new },
+                    None => Tick::Continue,
+                }
             }
-            _ => return false,
+            (None, _) => Tick::EndOfLife,
         }
-        true
     }
 }
 
@@ -273,31 +176,35 @@ enum Tick {
     EndOfLife,
 }
 
+const EXAMPLE_INPUT: &str = indoc!{r#"
+    .|...\....
+    |.-.\.....
+    .....|-...
+    ........|.
+    ..........
+    .........\
+    ..../.\\..
+    .-.-/..|..
+    .|....-|.\
+    ..//.|....
+"#};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT, Some(46), Some(51)),
+];
+
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use test_log::test;
+    use crate::registry::Part;
     use crate::util;
     use super::*;
 
-    const EXAMPLE_INPUT: &'static str = indoc!{r#"
-        .|...\....
-        |.-.\.....
-        .....|-...
-        ........|.
-        ..........
-        .........\
-        ..../.\\..
-        .-.-/..|..
-        .|....-|.\
-        ..//.|....
-    "#};
-
     #[test]
     fn test_solve_1() -> anyhow::Result<()> {
         assert_eq!(
-            solve_1(EXAMPLE_INPUT)?,
-            "46".to_string()
+            solve_1(EXAMPLE_INPUT)?.value,
+            46.into()
         );
         Ok(())
     }
@@ -311,15 +218,15 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_1(&input)?);
+        util::check_answer(day, Part::One, &solve_1(&input)?)?;
         Ok(())
     }
 
     #[test]
     fn test_solve_2() -> anyhow::Result<()> {
         assert_eq!(
-            solve_2(EXAMPLE_INPUT)?,
-            "51".to_string()
+            solve_2(EXAMPLE_INPUT)?.value,
+            51.into()
         );
         Ok(())
     }
@@ -333,7 +240,13 @@ mod tests {
             return Ok(());
         };
 
-        log::warn!("{}", solve_2(&input)?);
+        util::check_answer(day, Part::Two, &solve_2(&input)?)?;
         Ok(())
     }
+
+    #[test]
+    fn test_empty_input() {
+        assert_eq!(solve_1("").unwrap_err().to_string(), "Expect contraption tiles");
+        assert_eq!(solve_2("\n").unwrap_err().to_string(), "Expect contraption tiles");
+    }
 }
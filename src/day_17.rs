@@ -1,84 +1,199 @@
 use anyhow::Context;
 
-use itertools::Itertools;
+use indoc::indoc;
+
+use crate::grid::Grid;
+use crate::point::{Direction, Point};
+use crate::registry::Example;
+use crate::search;
+use crate::solution::Solution;
 
 pub fn solve_1(input: &str) -> crate::PuzzleResult {
-    // let data = parse(input)?;
+    let city = parse(input)?;
 
-    Ok("TODO".to_string())
+    find_min_heat_loss(&city, 1, 3)
 }
 
 pub fn solve_2(input: &str) -> crate::PuzzleResult {
-    // let data = parse(input)?;
+    let city = parse(input)?;
+
+    find_min_heat_loss(&city, 4, 10)
+}
+
+/// Crucible that moved `run` blocks in a straight line toward `dir` to reach `pos`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Crucible {
+    pos: Point,
+    dir: Direction,
+    run: u32,
+}
 
-    Ok("TODO".to_string())
+fn find_min_heat_loss(city: &Grid<u32>, min_run: u32, max_run: u32) -> crate::PuzzleResult {
+    let factory = Point::new(city.height() - 1, city.width() - 1);
+    let starts = [Direction::Right, Direction::Down]
+        .map(|dir| Crucible { pos: Point::new(0, 0), dir, run: 0 });
+
+    let neighbors = |crucible: &Crucible| {
+        let Crucible { pos, dir, run } = *crucible;
+        [dir, dir.turn_left(), dir.turn_right()]
+            .into_iter()
+            .filter(move |&next_dir| {
+                if next_dir == dir {
+                    run < max_run
+                } else {
+                    run >= min_run
+                }
+            })
+            .filter_map(move |next_dir| {
+                let next_pos = city.step(pos, next_dir)?;
+                let next_run = if next_dir == dir { run + 1 } else { 1 };
+                let next = Crucible { pos: next_pos, dir: next_dir, run: next_run };
+                Some((next, city[next_pos] as u64))
+            })
+    };
+    let is_goal = |crucible: &Crucible| crucible.pos == factory && crucible.run >= min_run;
+    // Every block to the factory loses at least the minimal heat
+    let min_block_heat_loss = city.values().copied().min().unwrap_or(0) as u64;
+    let estimate = |crucible: &Crucible| {
+        crucible.pos.manhattan_distance(factory) as u64 * min_block_heat_loss
+    };
+
+    let search = search::astar(starts, neighbors, estimate, is_goal);
+    let goal = search.goal().context("Cannot reach the factory")?;
+    let path = search.path_to(goal).context("Expect path to the factory")?;
+    let solution = Solution::from(search.goal_cost().context("Expect heat loss")?)
+        .with_diagnostic("Visited states", search.visited().count())
+        .with_visualization(render_path(city, &path));
+    Ok(solution)
 }
 
-fn parse(input: &str) -> anyhow::Result<Vec<()>> {
-    let lines = input.lines();
-    let mut records = vec!();
-    for line in lines {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
+/// Draws the path over the city the same way as the puzzle description does
+fn render_path(city: &Grid<u32>, path: &[Crucible]) -> String {
+    let mut map = city.map(|heat_loss| char::from_digit(*heat_loss, 10).unwrap_or('?'));
+    for crucible in path.iter().skip(1) {
+        map[crucible.pos] = match crucible.dir {
+            Direction::Up => '^',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+            Direction::Right => '>',
+        };
     }
+    map.to_string()
+}
 
-    Ok(records)
+fn parse(input: &str) -> anyhow::Result<Grid<u32>> {
+    let city = Grid::parse_with(input, |c| c.to_digit(10))?;
+    if city.width() == 0 {
+        anyhow::bail!("Expect city blocks");
+    }
+    Ok(city)
 }
 
+const EXAMPLE_INPUT_1: &str = indoc!{"
+    2413432311323
+    3215453535623
+    3255245654254
+    3446585845452
+    4546657867536
+    1438598798454
+    4457876987766
+    3637877979653
+    4654967986887
+    4564679986453
+    1224686865563
+    2546548887735
+    4322674655533
+"};
+const EXAMPLE_INPUT_2: &str = indoc!{"
+    111111111111
+    999999999991
+    999999999991
+    999999999991
+    999999999991
+"};
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE_INPUT_1, Some(102), Some(94)),
+    Example::new(EXAMPLE_INPUT_2, None, Some(71)),
+];
+
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use test_log::test;
+    use crate::registry::Part;
     use crate::util;
     use super::*;
 
-    const EXAMPLE_INPUT: &'static str = indoc!{"
+    #[test]
+    fn test_solve_1() -> anyhow::Result<()> {
+        assert_eq!(
+            solve_1(EXAMPLE_INPUT_1)?.value,
+            102.into()
+        );
+        Ok(())
+    }
 
-    "};
+    #[test]
+    fn solve_1_with_user_input() -> anyhow::Result<()> {
+        let day = util::day_from_filename(file!())?;
+        let input = if let Some(input) = util::fetch_user_input(day)? {
+            input
+        } else {
+            return Ok(());
+        };
+
+        util::check_answer(day, Part::One, &solve_1(&input)?)?;
+        Ok(())
+    }
 
     #[test]
-    fn test_solve_1() -> anyhow::Result<()> {
+    fn test_solve_2() -> anyhow::Result<()> {
+        assert_eq!(
+            solve_2(EXAMPLE_INPUT_1)?.value,
+            94.into()
+        );
+        assert_eq!(
+            solve_2(EXAMPLE_INPUT_2)?.value,
+            71.into()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_path() -> anyhow::Result<()> {
+        let solution = solve_1(EXAMPLE_INPUT_1)?;
+        let visualization = solution.visualization.context("Expect visualization")?;
         assert_eq!(
-            solve_1(EXAMPLE_INPUT)?,
-            "".to_string()
+            visualization,
+            indoc!{"
+                2>>34^>>>1323
+                32v>>>35v5623
+                32552456v>>54
+                3446585845v52
+                4546657867v>6
+                14385987984v4
+                44578769877v6
+                36378779796v>
+                465496798688v
+                456467998645v
+                12246868655<v
+                25465488877v5
+                43226746555v>
+            "}
         );
         Ok(())
     }
 
-    // #[test]
-    // fn solve_1_with_user_input() -> anyhow::Result<()> {
-    //     let day = util::day_from_filename(file!())?;
-    //     let input = if let Some(input) = util::fetch_user_input(day)? {
-    //         input
-    //     } else {
-    //         return Ok(());
-    //     };
-
-    //     log::warn!("{}", solve_1(&input)?);
-    //     Ok(())
-    // }
-
-    // #[test]
-    // fn test_solve_2() -> anyhow::Result<()> {
-    //     assert_eq!(
-    //         solve_2(EXAMPLE_INPUT)?,
-    //         "".to_string()
-    //     );
-    //     Ok(())
-    // }
-
-    // #[test]
-    // fn solve_2_with_user_input() -> anyhow::Result<()> {
-    //     let day = util::day_from_filename(file!())?;
-    //     let input = if let Some(input) = util::fetch_user_input(day)? {
-    //         input
-    //     } else {
-    //         return Ok(());
-    //     };
-
-    //     log::warn!("{}", solve_2(&input)?);
-    //     Ok(())
-    // }
+    #[test]
+    fn solve_2_with_user_input() -> anyhow::Result<()> {
+        let day = util::day_from_filename(file!())?;
+        let input = if let Some(input) = util::fetch_user_input(day)? {
+            input
+        } else {
+            return Ok(());
+        };
+
+        util::check_answer(day, Part::Two, &solve_2(&input)?)?;
+        Ok(())
+    }
 }
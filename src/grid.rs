@@ -0,0 +1,295 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::parse_error::{self, Line, ParseError};
+use crate::point::{Direction, Point};
+
+/// Rectangular grid stored row by row
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Parses a grid where every character is converted into a cell.
+    /// Blank lines around the grid are skipped
+    pub fn parse_with(
+        input: &str,
+        convert: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut grids = Self::parse_many_with(input, convert)?;
+        if grids.len() > 1 {
+            let line = parse_error::lines(input)
+                .filter(|line| !line.text.trim().is_empty())
+                .nth(grids[0].height)
+                .expect("Line of the second grid");
+            return Err(line.error(line.text.trim(), "Expect a single grid"));
+        }
+        Ok(grids.pop().unwrap_or_else(|| Self { width: 0, height: 0, cells: vec!() }))
+    }
+
+    /// Parses grids separated by blank lines
+    pub fn parse_many_with(
+        input: &str,
+        mut convert: impl FnMut(char) -> Option<T>,
+    ) -> Result<Vec<Self>, ParseError> {
+        let mut grids = vec!();
+        let mut cur: Option<Self> = None;
+        for line in parse_error::lines(input) {
+            let text = line.text.trim();
+            if text.is_empty() {
+                grids.extend(cur.take());
+                continue;
+            }
+            let grid = cur.get_or_insert_with(|| Self {
+                width: text.chars().count(),
+                height: 0,
+                cells: vec!(),
+            });
+            grid.push_row(&line, text, &mut convert)?;
+        }
+        grids.extend(cur);
+        Ok(grids)
+    }
+
+    fn push_row(
+        &mut self,
+        line: &Line,
+        text: &str,
+        convert: &mut impl FnMut(char) -> Option<T>,
+    ) -> Result<(), ParseError> {
+        let mut num_cols = 0;
+        for (offset, c) in text.char_indices() {
+            let cell = convert(c).ok_or_else(|| {
+                line.error(&text[offset..offset + c.len_utf8()], format!("Unexpected character '{c}'"))
+            })?;
+            self.cells.push(cell);
+            num_cols += 1;
+        }
+        if num_cols != self.width {
+            return Err(line.error(text, format!("Expect {} columns", self.width)));
+        }
+        self.height += 1;
+        Ok(())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.row < self.height && p.col < self.width
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.contains(p).then(|| &self.cells[p.row * self.width + p.col])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        if !self.contains(p) {
+            return None;
+        }
+        Some(&mut self.cells[p.row * self.width + p.col])
+    }
+
+    /// All the points in row-major order
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| Point::new(row, col)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn find(&self, mut pred: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter().find(|(_, v)| pred(v)).map(|(p, _)| p)
+    }
+
+    /// Moves the point toward the direction, `None` when it leaves the grid
+    pub fn step(&self, p: Point, dir: Direction) -> Option<Point> {
+        p.step(dir).filter(|&n| self.contains(n))
+    }
+
+    /// Points toward the direction up to the edge of the grid, the starting point is excluded
+    pub fn walk(&self, p: Point, dir: Direction) -> impl Iterator<Item = Point> + '_ {
+        std::iter::successors(self.step(p, dir), move |&n| self.step(n, dir))
+    }
+
+    /// Up, down, left and right neighbours that are inside the grid
+    pub fn neighbors4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL.into_iter().filter_map(move |dir| self.step(p, dir))
+    }
+
+    /// Neighbours including diagonal ones that are inside the grid
+    pub fn neighbors8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        const OFFSETS: [(isize, isize); 8] = [
+            (-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1),
+        ];
+        OFFSETS.into_iter()
+            .filter_map(move |offset| p.offset(offset))
+            .filter(|&n| self.contains(n))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn col(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "Column {col} is out of bounds");
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn rearrange(&self, width: usize, height: usize, src: impl Fn(Point) -> Point) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| Point::new(row, col)))
+            .map(|p| self[src(p)].clone())
+            .collect();
+        Self { width, height, cells }
+    }
+
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.rearrange(self.height, self.width, |p| Point::new(p.col, p.row))
+    }
+
+    /// Rotates clockwise so the left column becomes the top row
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        self.rearrange(self.height, self.width, |p| Point::new(height - 1 - p.col, p.row))
+    }
+}
+
+impl Grid<char> {
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        Self::parse_with(input, Some)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p).unwrap_or_else(|| panic!("Point {p:?} is out of the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        self.get_mut(p).unwrap_or_else(|| panic!("Point {p:?} is out of the grid"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use super::*;
+
+    const INPUT: &str = indoc!{"
+        abc
+        def
+    "};
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        let grid = Grid::parse(INPUT)?;
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[Point::new(1, 2)], 'f');
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+        assert_eq!(grid.to_string(), INPUT);
+        assert_eq!(grid.find(|&c| c == 'e'), Some(Point::new(1, 1)));
+
+        let err = Grid::parse("abc\nde\n").unwrap_err();
+        assert_eq!(err.to_string(), "Expect 3 columns at line 2, column 1");
+        let err = Grid::parse_with(".#\n#x", |c| (c != 'x').then_some(c)).unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected character 'x' at line 2, column 2");
+        let err = Grid::parse("ab\n\ncd\n").unwrap_err();
+        assert_eq!(err.to_string(), "Expect a single grid at line 3, column 1");
+
+        let grids = Grid::parse_many_with("ab\n\ncde\nfgh\n", Some)?;
+        assert_eq!(grids.len(), 2);
+        assert_eq!(grids[1].row(1), &['f', 'g', 'h']);
+        Ok(())
+    }
+
+    #[test]
+    fn test_neighbors() -> anyhow::Result<()> {
+        let grid = Grid::parse(INPUT)?;
+        let values = |points: Vec<Point>| points.into_iter().map(|p| grid[p]).collect::<String>();
+        assert_eq!(values(grid.neighbors4(Point::new(0, 0)).collect()), "db");
+        assert_eq!(values(grid.neighbors4(Point::new(1, 1)).collect()), "bdf");
+        assert_eq!(values(grid.neighbors8(Point::new(0, 2)).collect()), "bef");
+        assert_eq!(values(grid.neighbors8(Point::new(1, 1)).collect()), "abcdf");
+        Ok(())
+    }
+
+    #[test]
+    fn test_step_and_walk() -> anyhow::Result<()> {
+        let grid = Grid::parse(INPUT)?;
+        assert_eq!(grid.step(Point::new(0, 1), Direction::Down), Some(Point::new(1, 1)));
+        assert_eq!(grid.step(Point::new(1, 1), Direction::Down), None);
+        assert_eq!(grid.step(Point::new(0, 2), Direction::Right), None);
+        assert_eq!(
+            grid.walk(Point::new(1, 2), Direction::Left).map(|p| grid[p]).collect::<String>(),
+            "ed"
+        );
+        assert_eq!(grid.walk(Point::new(0, 0), Direction::Up).count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_views_and_rotation() -> anyhow::Result<()> {
+        let grid = Grid::parse(INPUT)?;
+        assert_eq!(grid.rows().collect::<Vec<_>>(), vec!(&['a', 'b', 'c'][..], &['d', 'e', 'f']));
+        assert_eq!(grid.col(1).collect::<String>(), "be");
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_cw().rotate_cw().to_string(), "fed\ncba\n");
+        assert_eq!(grid.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), grid);
+        assert_eq!(grid.map(|c| c.to_ascii_uppercase()).row(0), &['A', 'B', 'C']);
+        Ok(())
+    }
+}
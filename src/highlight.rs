@@ -0,0 +1,209 @@
+use std::fmt::Write;
+
+use crate::html::escape_html;
+use crate::lexer::{self, TokenKind};
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+    "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "type",
+    "unsafe", "use", "where", "while",
+];
+
+const PRIMITIVE_TYPES: &[&str] = &[
+    "bool", "char", "f32", "f64", "i8", "i16", "i32", "i64", "i128", "isize", "str",
+    "u8", "u16", "u32", "u64", "u128", "usize",
+];
+
+/// Highlights Rust code producing a table with line numbers.
+/// Class names are the same as highlight.js uses, see `public/highlight.css`
+pub fn highlight_rust(code: &str) -> String {
+    let mut lines = vec!(String::new());
+    for (class, text) in tokenize(code) {
+        for (ix, part) in text.split('\n').enumerate() {
+            if ix > 0 {
+                lines.push(String::new());
+            }
+            if part.is_empty() {
+                continue;
+            }
+            let line = lines.last_mut().expect("Current line");
+            match class {
+                Some(class) => {
+                    write!(line, "<span class=\"{class}\">{}</span>", escape_html(part)).ok();
+                }
+                None => line.push_str(&escape_html(part)),
+            }
+        }
+    }
+    if code.ends_with('\n') {
+        lines.pop();
+    }
+
+    let mut html = String::from("<table class=\"hljs-ln\"><tbody>");
+    for (ix, line) in lines.iter().enumerate() {
+        write!(
+            html,
+            "<tr><td class=\"hljs-ln-numbers\">{}</td><td class=\"hljs-ln-code\">{line}</td></tr>",
+            ix + 1
+        ).ok();
+    }
+    html.push_str("</tbody></table>");
+    html
+}
+
+/// Splits the code into fragments with optional highlight.js class names
+fn tokenize(code: &str) -> Vec<(Option<&'static str>, &str)> {
+    let lexed = lexer::tokenize(code);
+    let mut tokens: Vec<(Option<&'static str>, &str)> = vec!();
+    let mut prev_ident = "";
+    // Tokens are contiguous so the position is the sum of their lengths
+    let mut pos = 0;
+    let mut ix = 0;
+    while ix < lexed.len() {
+        let start = pos;
+        let (kind, text) = lexed[ix];
+        let next_text = lexed.get(ix + 1).map_or("", |(_, text)| *text);
+        let class = match kind {
+            TokenKind::Punct if text == "#" && matches!(next_text, "[" | "!") => {
+                let end = attribute_end(&lexed, ix);
+                pos += lexed[ix..end].iter().map(|(_, text)| text.len()).sum::<usize>();
+                ix = end;
+                Some("hljs-meta")
+            }
+            _ => {
+                pos += text.len();
+                ix += 1;
+                match kind {
+                    TokenKind::Comment => Some("hljs-comment"),
+                    TokenKind::Literal => Some("hljs-string"),
+                    TokenKind::Lifetime => Some("hljs-symbol"),
+                    TokenKind::Number => Some("hljs-number"),
+                    TokenKind::Ident => ident_class(text, prev_ident, next_text.bytes().next()),
+                    TokenKind::Whitespace | TokenKind::Punct => None,
+                }
+            }
+        };
+
+        match kind {
+            TokenKind::Ident => prev_ident = text,
+            TokenKind::Whitespace => {}
+            _ => prev_ident = "",
+        }
+        // Plain text can be merged with the previous fragment
+        match tokens.last_mut() {
+            Some((None, last_text)) if class.is_none() => {
+                *last_text = &code[start - last_text.len()..pos];
+            }
+            _ => tokens.push((class, &code[start..pos])),
+        }
+    }
+    tokens
+}
+
+fn ident_class(ident: &str, prev_ident: &str, next: Option<u8>) -> Option<&'static str> {
+    if KEYWORDS.contains(&ident) {
+        Some("hljs-keyword")
+    } else if ident == "true" || ident == "false" {
+        Some("hljs-literal")
+    } else if PRIMITIVE_TYPES.contains(&ident) {
+        Some("hljs-type")
+    } else if next == Some(b'!') {
+        Some("hljs-built_in")
+    } else if prev_ident == "fn" || next == Some(b'(') {
+        Some("hljs-title function_")
+    } else if ident.starts_with(|c: char| c.is_ascii_uppercase()) {
+        Some("hljs-type")
+    } else {
+        None
+    }
+}
+
+/// Returns the index of the token after the closing bracket of an attribute starting at `ix`.
+/// An unclosed attribute ends with its line
+fn attribute_end(tokens: &[(TokenKind, &str)], ix: usize) -> usize {
+    let mut depth = 0;
+    for (end, (kind, text)) in tokens.iter().enumerate().skip(ix) {
+        match (kind, *text) {
+            (TokenKind::Punct, "[") => depth += 1,
+            (TokenKind::Punct, "]") => {
+                depth -= 1;
+                if depth == 0 {
+                    return end + 1;
+                }
+            }
+            (TokenKind::Whitespace, _) if text.contains('\n') => return end,
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let code = r##"#[derive(Debug)] fn parse<'a>(s: &'a str) -> Vec<u8> { vec!('{', b'\n', 1.5, 0..n) } // "x""##;
+        let tokens = tokenize(code)
+            .into_iter()
+            .filter(|(class, _)| class.is_some())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            vec!(
+                (Some("hljs-meta"), "#[derive(Debug)]"),
+                (Some("hljs-keyword"), "fn"),
+                (Some("hljs-title function_"), "parse"),
+                (Some("hljs-symbol"), "'a"),
+                (Some("hljs-symbol"), "'a"),
+                (Some("hljs-type"), "str"),
+                (Some("hljs-type"), "Vec"),
+                (Some("hljs-type"), "u8"),
+                (Some("hljs-built_in"), "vec"),
+                (Some("hljs-string"), "'{'"),
+                (Some("hljs-string"), "'\\n'"),
+                (Some("hljs-number"), "1.5"),
+                (Some("hljs-number"), "0"),
+                (Some("hljs-comment"), "// \"x\""),
+            )
+        );
+
+        let code = r###"let s = r#"a "quoted" b"#;"###;
+        assert!(tokenize(code).contains(&(Some("hljs-string"), r###"r#"a "quoted" b"#"###)));
+
+        let code = r"let c = '\''; x";
+        assert_eq!(
+            tokenize(code),
+            vec!(
+                (Some("hljs-keyword"), "let"),
+                (None, " c = "),
+                (Some("hljs-string"), r"'\''"),
+                (None, "; x"),
+            )
+        );
+    }
+
+    #[test]
+    fn test_highlight_rust() {
+        let code = indoc!{r#"
+            let s = "a
+            <b>";
+        "#};
+        assert_eq!(
+            highlight_rust(code),
+            concat!(
+                "<table class=\"hljs-ln\"><tbody>",
+                "<tr><td class=\"hljs-ln-numbers\">1</td><td class=\"hljs-ln-code\">",
+                "<span class=\"hljs-keyword\">let</span> s = <span class=\"hljs-string\">\"a</span>",
+                "</td></tr>",
+                "<tr><td class=\"hljs-ln-numbers\">2</td><td class=\"hljs-ln-code\">",
+                "<span class=\"hljs-string\">&lt;b&gt;\"</span>;",
+                "</td></tr>",
+                "</tbody></table>",
+            )
+        );
+    }
+}
@@ -0,0 +1,17 @@
+/// Escapes text to put it between HTML tags
+pub fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(escape_html("a < b && b > c"), "a &lt; b &amp;&amp; b &gt; c");
+        assert_eq!(escape_html("&lt;"), "&amp;lt;");
+    }
+}
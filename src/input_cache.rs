@@ -0,0 +1,71 @@
+use std::env::var;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use anyhow::Context;
+
+/// Local directory with downloaded user inputs, one `day_XX.txt` file per day
+#[derive(Clone, Debug)]
+pub struct InputCache {
+    dir: PathBuf,
+}
+
+impl InputCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Uses `AOC_INPUT_CACHE` environment variable falling back to the `inputs`
+    /// directory in the project root
+    pub fn from_env() -> Self {
+        if let Ok(dir) = var("AOC_INPUT_CACHE") {
+            Self::new(dir)
+        } else {
+            Self::new(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("inputs"))
+        }
+    }
+
+    pub fn input_path(&self, day: u32) -> PathBuf {
+        self.dir.join(format!("day_{day:0>2}.txt"))
+    }
+
+    pub fn read(&self, day: u32) -> anyhow::Result<Option<String>> {
+        let path = self.input_path(day);
+        match fs::read_to_string(&path) {
+            Ok(input) if input.is_empty() => Ok(None),
+            Ok(input) => Ok(Some(input)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e)
+                .with_context(|| format!("Cannot read cached input: {}", path.display())),
+        }
+    }
+
+    pub fn write(&self, day: u32, input: &str) -> anyhow::Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Cannot create input cache: {}", self.dir.display()))?;
+        let path = self.input_path(day);
+        fs::write(&path, input)
+            .with_context(|| format!("Cannot write cached input: {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::TempDir;
+    use super::*;
+
+    #[test]
+    fn test_read_and_write() -> anyhow::Result<()> {
+        let dir = TempDir::new("input-cache");
+        let cache = InputCache::new(dir.path());
+
+        assert_eq!(cache.input_path(5), dir.path().join("day_05.txt"));
+        assert_eq!(cache.read(5)?, None);
+
+        cache.write(5, "seeds: 79 14 55 13\n")?;
+        assert_eq!(cache.read(5)?, Some("seeds: 79 14 55 13\n".to_string()));
+        assert_eq!(cache.read(6)?, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,310 @@
+use std::cmp::{max, min};
+use std::fmt;
+
+/// Half-open interval `[start, end)`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub const fn new(start: i64, end: i64) -> Self {
+        Self { start, end }
+    }
+
+    pub const fn with_len(start: i64, len: i64) -> Self {
+        Self { start, end: start + len }
+    }
+
+    pub fn len(&self) -> i64 {
+        max(self.end - self.start, 0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        self.start <= x && x < self.end
+    }
+
+    pub fn shift(&self, dist: i64) -> Self {
+        Self::new(self.start + dist, self.end + dist)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let res = Self::new(max(self.start, other.start), min(self.end, other.end));
+        (!res.is_empty()).then_some(res)
+    }
+
+    /// Parts of the interval that are outside of the other one
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if self.intersection(other).is_none() {
+            return vec!(*self);
+        }
+        [Self::new(self.start, other.start), Self::new(other.end, self.end)]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect()
+    }
+
+    /// Single interval when the intervals overlap or touch each other
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        if self.start > other.end || other.start > self.end {
+            return None;
+        }
+        Some(Self::new(min(self.start, other.start), max(self.end, other.end)))
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// Sorts intervals and merges the ones that overlap or touch
+pub fn normalize(intervals: impl IntoIterator<Item = Interval>) -> Vec<Interval> {
+    let mut intervals = intervals.into_iter()
+        .filter(|interval| !interval.is_empty())
+        .collect::<Vec<_>>();
+    intervals.sort();
+    let mut merged: Vec<Interval> = vec!();
+    for interval in intervals {
+        match merged.last_mut().and_then(|last| Some((last.union(&interval)?, last))) {
+            Some((union, last)) => *last = union,
+            None => merged.push(interval),
+        }
+    }
+    merged
+}
+
+/// Map that shifts values of every interval by its own distance.
+/// Values outside of the intervals are mapped to themselves
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PiecewiseMap {
+    /// Sorted non-overlapping intervals with non-zero shifts
+    pieces: Vec<(Interval, i64)>,
+}
+
+impl PiecewiseMap {
+    pub fn new(pieces: impl IntoIterator<Item = (Interval, i64)>) -> anyhow::Result<Self> {
+        let mut pieces = pieces.into_iter()
+            .filter(|(interval, shift)| !interval.is_empty() && *shift != 0)
+            .collect::<Vec<_>>();
+        pieces.sort();
+        for pair in pieces.windows(2) {
+            if pair[0].0.end > pair[1].0.start {
+                anyhow::bail!("Intervals {:?} and {:?} overlap", pair[0].0, pair[1].0);
+            }
+        }
+        Ok(Self::from_sorted(pieces))
+    }
+
+    /// Merges adjacent pieces with the same shift, pieces must be sorted and not overlap
+    fn from_sorted(pieces: impl IntoIterator<Item = (Interval, i64)>) -> Self {
+        let mut merged: Vec<(Interval, i64)> = vec!();
+        for (interval, shift) in pieces {
+            if interval.is_empty() || shift == 0 {
+                continue;
+            }
+            match merged.last_mut() {
+                Some((last, last_shift)) if *last_shift == shift && last.end == interval.start => {
+                    last.end = interval.end;
+                }
+                _ => merged.push((interval, shift)),
+            }
+        }
+        Self { pieces: merged }
+    }
+
+    /// Pieces together with the gaps between them, covers all the values
+    pub fn segments(&self) -> Vec<(Interval, i64)> {
+        let mut segments = vec!();
+        let mut gap_start = i64::MIN;
+        for &(interval, shift) in &self.pieces {
+            segments.push((Interval::new(gap_start, interval.start), 0));
+            segments.push((interval, shift));
+            gap_start = interval.end;
+        }
+        segments.push((Interval::new(gap_start, i64::MAX), 0));
+        segments.retain(|(interval, _)| !interval.is_empty());
+        segments
+    }
+
+    pub fn map_point(&self, x: i64) -> i64 {
+        self.pieces.iter()
+            .find(|(interval, _)| interval.contains(x))
+            .map_or(x, |(_, shift)| x + shift)
+    }
+
+    /// Images of the parts covered by the pieces followed by the parts outside of them
+    pub fn map_interval(&self, interval: &Interval) -> Vec<Interval> {
+        let mut mapped = vec!();
+        let mut not_mapped = vec!(*interval);
+        for (piece, shift) in &self.pieces {
+            if let Some(part) = interval.intersection(piece) {
+                mapped.push(part.shift(*shift));
+                not_mapped = not_mapped.iter()
+                    .flat_map(|rest| rest.difference(piece))
+                    .collect();
+            }
+        }
+        mapped.extend(not_mapped);
+        mapped
+    }
+
+    /// All the values that are mapped to `y`
+    pub fn preimage(&self, y: i64) -> Vec<i64> {
+        self.segments()
+            .into_iter()
+            .filter_map(|(segment, shift)| {
+                let x = y.checked_sub(shift)?;
+                segment.contains(x).then_some(x)
+            })
+            .collect()
+    }
+
+    /// Fails when some values have several preimages or none of them
+    pub fn inverse(&self) -> anyhow::Result<PiecewiseMap> {
+        let mut images = self.segments()
+            .into_iter()
+            .map(|(segment, shift)| (segment.shift(shift), -shift))
+            .collect::<Vec<_>>();
+        images.sort();
+        for pair in images.windows(2) {
+            let (prev, next) = (pair[0].0, pair[1].0);
+            if prev.end > next.start {
+                anyhow::bail!("Values {} have several preimages", Interval::new(next.start, prev.end));
+            }
+            if prev.end < next.start {
+                anyhow::bail!("Values {} have no preimage", Interval::new(prev.end, next.start));
+            }
+        }
+        Ok(Self::from_sorted(images))
+    }
+
+    /// Map that applies this map and then the next one
+    pub fn then(&self, next: &PiecewiseMap) -> PiecewiseMap {
+        let next_segments = next.segments();
+        let mut pieces = vec!();
+        for (segment, shift) in self.segments() {
+            let image = segment.shift(shift);
+            for (next_segment, next_shift) in &next_segments {
+                if let Some(part) = image.intersection(next_segment) {
+                    pieces.push((part.shift(-shift), shift + next_shift));
+                }
+            }
+        }
+        // Images of sorted segments are not sorted anymore
+        pieces.sort();
+        Self::from_sorted(pieces)
+    }
+}
+
+/// Every line is a piece with the source, the destination and the shift
+impl fmt::Display for PiecewiseMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (interval, shift) in &self.pieces {
+            writeln!(f, "{interval} -> {} ({shift:+})", interval.shift(*shift))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval() {
+        let a = Interval::new(2, 8);
+        assert_eq!(a.len(), 6);
+        assert_eq!(Interval::with_len(5, 3), Interval::new(5, 8));
+        assert!(a.contains(2) && !a.contains(8));
+        assert_eq!(a.shift(-2), Interval::new(0, 6));
+
+        assert_eq!(a.intersection(&Interval::new(5, 10)), Some(Interval::new(5, 8)));
+        assert_eq!(a.intersection(&Interval::new(8, 10)), None);
+
+        assert_eq!(a.difference(&Interval::new(4, 5)), vec!(Interval::new(2, 4), Interval::new(5, 8)));
+        assert_eq!(a.difference(&Interval::new(0, 5)), vec!(Interval::new(5, 8)));
+        assert_eq!(a.difference(&Interval::new(0, 10)), vec!());
+        assert_eq!(a.difference(&Interval::new(8, 10)), vec!(a));
+
+        assert_eq!(a.union(&Interval::new(8, 10)), Some(Interval::new(2, 10)));
+        assert_eq!(a.union(&Interval::new(9, 10)), None);
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize([
+                Interval::new(10, 12),
+                Interval::new(0, 3),
+                Interval::new(3, 4),
+                Interval::new(5, 5),
+                Interval::new(11, 15),
+            ]),
+            vec!(Interval::new(0, 4), Interval::new(10, 15))
+        );
+    }
+
+    #[test]
+    fn test_map() -> anyhow::Result<()> {
+        let map = PiecewiseMap::new([(Interval::new(10, 20), 5), (Interval::new(0, 5), -1)])?;
+        assert_eq!(map.map_point(-1), -1);
+        assert_eq!(map.map_point(0), -1);
+        assert_eq!(map.map_point(7), 7);
+        assert_eq!(map.map_point(19), 24);
+        assert_eq!(map.map_point(20), 20);
+        assert_eq!(
+            map.map_interval(&Interval::new(3, 12)),
+            vec!(Interval::new(2, 4), Interval::new(15, 17), Interval::new(5, 10))
+        );
+
+        assert_eq!(map.to_string(), "0..5 -> -1..4 (-1)\n10..20 -> 15..25 (+5)\n");
+
+        assert!(PiecewiseMap::new([(Interval::new(0, 5), 1), (Interval::new(4, 6), 2)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_then() -> anyhow::Result<()> {
+        let first = PiecewiseMap::new([(Interval::new(0, 10), 10)])?;
+        let second = PiecewiseMap::new([(Interval::new(15, 25), -15), (Interval::new(0, 5), 15)])?;
+        let composed = first.then(&second);
+        for x in -5..30 {
+            assert_eq!(composed.map_point(x), second.map_point(first.map_point(x)), "{x}");
+        }
+        assert_eq!(
+            composed.pieces,
+            vec!(
+                (Interval::new(0, 5), 10),
+                (Interval::new(5, 10), -5),
+                (Interval::new(15, 25), -15),
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_inverse() -> anyhow::Result<()> {
+        // Swaps 0..5 and 10..15
+        let map = PiecewiseMap::new([(Interval::new(0, 5), 10), (Interval::new(10, 15), -10)])?;
+        assert_eq!(map.preimage(12), vec!(2));
+        assert_eq!(map.preimage(7), vec!(7));
+        let inverse = map.inverse()?;
+        assert_eq!(inverse, map);
+        for x in -5..20 {
+            assert_eq!(inverse.map_point(map.map_point(x)), x);
+        }
+
+        let map = PiecewiseMap::new([(Interval::new(0, 5), 3)])?;
+        assert_eq!(map.preimage(6), vec!(3, 6));
+        assert!(map.preimage(1).is_empty());
+        assert_eq!(map.inverse().unwrap_err().to_string(), "Values 0..3 have no preimage");
+        Ok(())
+    }
+}
@@ -0,0 +1,197 @@
+/// Kinds of tokens that are enough to highlight the code and to find items in it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    Whitespace,
+    Comment,
+    /// String, byte string, raw string or character literal
+    Literal,
+    Lifetime,
+    Number,
+    Ident,
+    /// Single character
+    Punct,
+}
+
+/// Very rough Rust lexer. Tokens cover the whole code without gaps
+/// so their positions can be restored by summing the lengths
+pub fn tokenize(code: &str) -> Vec<(TokenKind, &str)> {
+    let bytes = code.as_bytes();
+    let mut tokens = vec!();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let c = bytes[i];
+        let next = bytes.get(i + 1).copied();
+        let kind = match c {
+            _ if c.is_ascii_whitespace() => {
+                while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+                TokenKind::Whitespace
+            }
+            b'/' if next == Some(b'/') => {
+                i = find_from(bytes, i, b"\n").unwrap_or(bytes.len());
+                TokenKind::Comment
+            }
+            b'/' if next == Some(b'*') => {
+                i = find_from(bytes, i + 2, b"*/").map_or(bytes.len(), |end| end + 2);
+                TokenKind::Comment
+            }
+            b'"' => {
+                i = skip_string(bytes, i + 1);
+                TokenKind::Literal
+            }
+            b'\'' => {
+                if next == Some(b'\\') {
+                    // The escaped character can be a quote itself
+                    let escaped_len = code[i + 2..].chars().next().map_or(0, char::len_utf8);
+                    i = find_from(bytes, i + 2 + escaped_len, b"'").map_or(bytes.len(), |end| end + 1);
+                    TokenKind::Literal
+                } else if let Some(end) = char_literal_end(code, i) {
+                    i = end;
+                    TokenKind::Literal
+                } else {
+                    i += 1;
+                    while i < bytes.len() && is_ident_byte(bytes[i]) {
+                        i += 1;
+                    }
+                    TokenKind::Lifetime
+                }
+            }
+            _ if c.is_ascii_digit() => {
+                while i < bytes.len() && (is_ident_byte(bytes[i]) || is_fraction(bytes, i)) {
+                    i += 1;
+                }
+                TokenKind::Number
+            }
+            _ if is_ident_byte(c) => {
+                while i < bytes.len() && is_ident_byte(bytes[i]) {
+                    i += 1;
+                }
+                match (&code[start..i], bytes.get(i)) {
+                    ("r" | "br", Some(b'"' | b'#')) if is_raw_string(bytes, i) => {
+                        i = skip_raw_string(bytes, i);
+                        TokenKind::Literal
+                    }
+                    ("b", Some(b'"')) => {
+                        i = skip_string(bytes, i + 1);
+                        TokenKind::Literal
+                    }
+                    _ => TokenKind::Ident,
+                }
+            }
+            _ => {
+                // Keeps multibyte characters whole
+                i += code[i..].chars().next().map_or(1, char::len_utf8);
+                TokenKind::Punct
+            }
+        };
+        tokens.push((kind, &code[start..i]));
+    }
+    tokens
+}
+
+fn is_ident_byte(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_'
+}
+
+/// Dot followed by a digit inside a number, `0..n` is a range
+fn is_fraction(bytes: &[u8], i: usize) -> bool {
+    bytes[i] == b'.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+}
+
+fn find_from(bytes: &[u8], from: usize, pattern: &[u8]) -> Option<usize> {
+    bytes.get(from..)?
+        .windows(pattern.len())
+        .position(|w| w == pattern)
+        .map(|pos| from + pos)
+}
+
+/// Returns the end of a character literal starting at `i` or `None` for a lifetime
+fn char_literal_end(code: &str, i: usize) -> Option<usize> {
+    let c = code[i + 1..].chars().next()?;
+    let end = i + 1 + c.len_utf8();
+    (code.as_bytes().get(end) == Some(&b'\'')).then_some(end + 1)
+}
+
+/// Returns position after the closing quote, `i` points after the opening quote
+fn skip_string(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn is_raw_string(bytes: &[u8], i: usize) -> bool {
+    bytes[i..].iter().find(|&&c| c != b'#') == Some(&b'"')
+}
+
+/// Returns position after the raw string, `i` points to the first `#` or the opening quote
+fn skip_raw_string(bytes: &[u8], i: usize) -> usize {
+    let hashes = bytes[i..].iter().take_while(|&&c| c == b'#').count();
+    let mut terminator = vec!(b'"');
+    terminator.extend(std::iter::repeat_n(b'#', hashes));
+    find_from(bytes, i + hashes + 1, &terminator).map_or(bytes.len(), |end| end + terminator.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn non_whitespace(code: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(code)
+            .into_iter()
+            .filter(|(kind, _)| *kind != TokenKind::Whitespace)
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let code = "fn f<'a>(s: &'a str) { /* x */ 1.5 } // y\n";
+        assert_eq!(tokenize(code).iter().map(|(_, text)| *text).collect::<String>(), code);
+        assert_eq!(
+            non_whitespace(code),
+            vec!(
+                (TokenKind::Ident, "fn"),
+                (TokenKind::Ident, "f"),
+                (TokenKind::Punct, "<"),
+                (TokenKind::Lifetime, "'a"),
+                (TokenKind::Punct, ">"),
+                (TokenKind::Punct, "("),
+                (TokenKind::Ident, "s"),
+                (TokenKind::Punct, ":"),
+                (TokenKind::Punct, "&"),
+                (TokenKind::Lifetime, "'a"),
+                (TokenKind::Ident, "str"),
+                (TokenKind::Punct, ")"),
+                (TokenKind::Punct, "{"),
+                (TokenKind::Comment, "/* x */"),
+                (TokenKind::Number, "1.5"),
+                (TokenKind::Punct, "}"),
+                (TokenKind::Comment, "// y"),
+            )
+        );
+    }
+
+    #[test]
+    fn test_literals() {
+        let code = r###"'\'' '\\' '{' '\u{7d}' "\"}" b"}" r#"a "}" b"# é"###;
+        assert_eq!(
+            non_whitespace(code),
+            vec!(
+                (TokenKind::Literal, r"'\''"),
+                (TokenKind::Literal, r"'\\'"),
+                (TokenKind::Literal, "'{'"),
+                (TokenKind::Literal, r"'\u{7d}'"),
+                (TokenKind::Literal, r#""\"}""#),
+                (TokenKind::Literal, r#"b"}""#),
+                (TokenKind::Literal, r###"r#"a "}" b"#"###),
+                (TokenKind::Punct, "é"),
+            )
+        );
+    }
+}
@@ -1,60 +1,80 @@
 #![allow(non_snake_case)]
-use std::collections::HashMap;
-use std::rc::Rc;
+use std::time::Duration;
 
 use dioxus::prelude::*;
 
 use paste::paste;
 
-use web_sys;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
 
+use web_sys::{self, HtmlTextAreaElement, Location};
+
+use answers::Verdict;
+use parse_error::ParseError;
+use registry::{Day, Part, PuzzleId, Status};
+use solution::Solution;
+use worker::SolverWorker;
+
+mod answers;
+mod bench;
+mod cli;
+mod cycle;
+mod grid;
+mod highlight;
+mod html;
+mod input_cache;
+mod interval;
+mod lexer;
+mod parse_error;
+mod point;
+mod registry;
+mod search;
+mod solution;
+mod source_map;
+mod storage;
+mod worker;
 #[cfg(test)]
 mod util;
 
-pub type PuzzleResult = anyhow::Result<String>;
+pub type PuzzleResult = anyhow::Result<Solution>;
 
 macro_rules! days {
-    ($($day:expr),*) => {
+    ($($day:literal $(: $status:ident)?),*) => {
         paste! {
             $(mod [<day_ $day>];)*
 
-            const DAYS: &'static [Day] = &[
+            // A static keeps a single lazily built source map per day
+            static DAYS: [Day; [$($day),*].len()] = [
                 $(
                     Day::new(
                         $day,
                         include_str!(concat!("day_", stringify!($day), ".rs")),
                         [<day_ $day>]::solve_1,
                         [<day_ $day>]::solve_2,
-                    ),
+                        [<day_ $day>]::EXAMPLES,
+                    )$(.with_status(Status::$status))?,
                 )*
             ];
         }
     };
 }
 
-days!(01, 02, 03, 04, 05, 06, 07, 08, 09, 10, 11, 13, 14, 15, 16);
-
-struct Day {
-    pub ord: u32,
-    pub code: &'static str,
-    pub solve1: fn(&str) -> PuzzleResult,
-    pub solve2: fn(&str) -> PuzzleResult,
-}
+days!(01, 02, 03, 04, 05, 06, 07, 08, 09, 10, 11, 12, 13, 14, 15, 16, 17);
 
-impl Day {
-    const fn new(
-        ord: u32,
-        code: &'static str,
-        solve1: fn(&str) -> PuzzleResult,
-        solve2: fn(&str) -> PuzzleResult,
-    ) -> Self {
-        Self { ord, code, solve1, solve2 }
-    }
-}
+const SOLVER_TIMEOUT: Duration = Duration::from_secs(60);
 
 fn main() {
     dioxus_logger::init(log::LevelFilter::Info).expect("failed to init logger");
-    dioxus_web::launch(App);
+    if cfg!(target_arch = "wasm32") {
+        // There is no window inside the solver web worker
+        if web_sys::window().is_some() {
+            dioxus_web::launch(App);
+        }
+    } else if let Err(e) = cli::run(std::env::args().skip(1)) {
+        eprintln!("Error: {e:#}");
+        std::process::exit(1);
+    }
 }
 
 // create a component that renders a div with the text "Hello, world!"
@@ -74,45 +94,102 @@ fn App(cx: Scope) -> Element {
 }
 
 fn Solver(cx: Scope) -> Element {
-    let src = use_state(cx, || DAYS.last().unwrap().code);
-    let answer = use_state(cx, || None);
-
     let window = web_sys::window().expect("Window object");
-    let perf = window.performance().expect("Performance");
     let location = window.location();
-    let hash = location.hash();
-    let cur_puzzle_id = if let Ok(Some(cur_puzzle)) = hash.as_ref().map(|h| h.strip_prefix("#")) {
-        cur_puzzle.to_string()
-    } else {
-        "01-1".to_string()
+    let cur_puzzle_id = puzzle_id_from_hash(&location);
+    let cur_day_ord = cur_puzzle_id.day;
+
+    let cur_day = use_state(cx, || {
+        registry::find_day(cur_day_ord)
+            .unwrap_or_else(|| registry::days().last().unwrap())
+    });
+    let input = use_state(cx, || storage::load_input(cur_day_ord).unwrap_or_default());
+    // The hash also changes when it is edited by hand or when navigating through history
+    cx.use_hook(|| {
+        let set_cur_day = cur_day.setter();
+        let set_input = input.setter();
+        let location = location.clone();
+        let on_hash_change = Closure::<dyn FnMut()>::new(move || {
+            if let Some(day) = registry::find_day(puzzle_id_from_hash(&location).day) {
+                set_cur_day(day);
+                set_input(storage::load_input(day.ord).unwrap_or_default());
+            }
+        });
+        window.set_onhashchange(Some(on_hash_change.as_ref().unchecked_ref()));
+        on_hash_change
+    });
+    let answer = use_state(cx, || None::<anyhow::Result<Vec<SolvedPuzzle>>>);
+    let running = use_state(cx, || false);
+    let solver_worker = use_ref(cx, || None::<SolverWorker>);
+    // Input of the running solvers, it is needed to check answers of examples
+    let solving_input = use_ref(cx, String::new);
+
+    let start_solving = move |puzzle_ids: &[PuzzleId]| {
+        if *running.get() {
+            return;
+        }
+        let mut solver_worker = solver_worker.write();
+        if solver_worker.as_ref().is_none_or(SolverWorker::is_terminated) {
+            let set_answer = answer.setter();
+            let set_running = running.setter();
+            let solving_input = solving_input.clone();
+            let spawned = SolverWorker::spawn(SOLVER_TIMEOUT, move |res| {
+                let res = res.map(|results| {
+                    results.into_iter()
+                        .map(|(puzzle_id, outcome)| {
+                            let outcome = outcome.map(|(solution, duration)| {
+                                let verdict = answers::verify_input(
+                                    puzzle_id, &solving_input.read(), &solution.value
+                                );
+                                (solution, verdict, duration)
+                            });
+                            (puzzle_id, outcome)
+                        })
+                        .collect::<Vec<_>>()
+                });
+                let parse_error = res.as_ref().ok().and_then(|results| {
+                    results.iter()
+                        .find_map(|(_, outcome)| outcome.as_ref().err()?.downcast_ref::<ParseError>())
+                });
+                if let Some(parse_error) = parse_error {
+                    select_input_line(parse_error.line);
+                }
+                set_answer(Some(res));
+                set_running(false);
+            });
+            match spawned {
+                Ok(worker) => *solver_worker = Some(worker),
+                Err(e) => {
+                    answer.set(Some(Err(e)));
+                    return;
+                }
+            }
+        }
+        *solving_input.write() = input.get().clone();
+        let res = solver_worker.as_ref()
+            .expect("Solver worker")
+            .solve(puzzle_ids, input.get());
+        match res {
+            Ok(()) => {
+                running.set(true);
+                answer.set(None);
+            }
+            Err(e) => answer.set(Some(Err(e))),
+        }
     };
-    let puzzle_solvers = DAYS.iter()
-        .flat_map(|d| {
-            [
-                (format!("{:0>2}-1", d.ord), d.solve1),
-                (format!("{:0>2}-2", d.ord), d.solve2)
-            ]
-        })
-        .collect::<HashMap<_, _>>();
 
     render!{
         form {
             onsubmit: move |event| {
-                let input = &event.data.values["input"][0];
-                let puzzle_id = event.data.values["puzzle"][0].as_str();
-                let res = if let Some(solver) = &puzzle_solvers.get(puzzle_id) {
-                    let start = perf.now();
-                    let res = solver(input);
-                    let end = perf.now();
-                    res.map(|r| (r, end - start))
-                } else {
-                    Err(anyhow::anyhow!("Invalid task"))
-                };
-                answer.set(Some(res));
+                match event.data.values["puzzle"][0].parse::<PuzzleId>() {
+                    Ok(puzzle_id) => start_solving(&[puzzle_id]),
+                    Err(e) => answer.set(Some(Err(e))),
+                }
             },
             Puzzles {
-                cur_puzzle_id: cur_puzzle_id,
-                src: src,
+                cur_puzzle_id: cur_puzzle_id.to_string(),
+                cur_day: cur_day,
+                input: input,
             }
             div {
                 class: "columns-1 xl:columns-2 gap-2",
@@ -120,27 +197,67 @@ fn Solver(cx: Scope) -> Element {
                     class: "w-full",
                     div {
                         class: "py-2",
-                        legend {
-                            "Input data"
+                        div {
+                            class: "flex justify-between items-center pb-1",
+                            legend {
+                                "Input data"
+                            }
+                            ExamplePicker {
+                                day_ord: cur_day_ord,
+                                input: input,
+                            }
                         }
                         textarea {
+                            id: "input",
                             name: "input",
                             placeholder: "Paste your input data",
                             class: "block p-2 w-full resize border bg-gray-50 font-mono",
                             rows: "20",
+                            value: "{input}",
+                            oninput: move |event| {
+                                storage::save_input(cur_day_ord, &event.value);
+                                input.set(event.value.clone());
+                            },
                         }
                     }
                     div {
-                        class: "grid grid-cols-4",
+                        class: "flex gap-2",
                         div {
+                            class: "flex gap-2 items-start",
                             button {
-                                class: "inline-flex justify-center px-4 py-1 text-white bg-blue-600 border border-blue-700 rounded-md",
+                                class: "inline-flex justify-center px-4 py-1 text-white bg-blue-600 border border-blue-700 rounded-md disabled:opacity-50",
+                                disabled: *running.get(),
                                 "Solve it"
                             }
+                            button {
+                                r#type: "button",
+                                class: "inline-flex justify-center px-4 py-1 text-blue-600 border border-blue-600 rounded-md disabled:opacity-50",
+                                disabled: *running.get(),
+                                onclick: move |_| {
+                                    start_solving(&Part::ALL.map(|part| PuzzleId::new(cur_day_ord, part)));
+                                },
+                                "Solve both"
+                            }
                         }
                         div {
-                            class: "col-span-3 py-1",
-                            Answer { answer: answer.get() }
+                            class: "flex-1 py-1",
+                            if *running.get() {
+                                rsx!{
+                                    Running {
+                                        on_cancel: move |_| {
+                                            if let Some(solver_worker) = solver_worker.read().as_ref() {
+                                                solver_worker.cancel();
+                                            }
+                                            running.set(false);
+                                            answer.set(Some(Err(anyhow::anyhow!("Cancelled"))));
+                                        }
+                                    }
+                                }
+                            } else {
+                                rsx!{
+                                    Answer { answer: answer.get() }
+                                }
+                            }
                         }
                     }
                 }
@@ -148,7 +265,8 @@ fn Solver(cx: Scope) -> Element {
                     id: "code",
                     class: "border p-2 w-full overflow-x-auto",
                     Source {
-                        code: src.to_string()
+                        day: cur_day.get(),
+                        part: cur_puzzle_id.part,
                     }
                 }
             }
@@ -156,20 +274,91 @@ fn Solver(cx: Scope) -> Element {
     }
 }
 
+fn puzzle_id_from_hash(location: &Location) -> PuzzleId {
+    location.hash().ok()
+        .and_then(|h| h.strip_prefix('#')?.parse::<PuzzleId>().ok())
+        .unwrap_or(PuzzleId::new(1, Part::One))
+}
+
+#[inline_props]
+fn ExamplePicker<'a>(cx: Scope<'a>, day_ord: u32, input: &'a UseState<String>) -> Element<'a> {
+    let examples = registry::find_day(*day_ord)
+        .map(|day| day.examples)
+        .unwrap_or_default();
+
+    render!{
+        select {
+            class: "px-2 py-0.5 border rounded-md",
+            value: "",
+            onchange: move |event| {
+                let example = event.value.parse::<usize>().ok()
+                    .and_then(|ix| examples.get(ix));
+                if let Some(example) = example {
+                    storage::save_input(*day_ord, example.input);
+                    input.set(example.input.to_string());
+                }
+            },
+            option {
+                value: "",
+                disabled: true,
+                "Load example"
+            }
+            examples.iter().enumerate().map(|(ix, example)| {
+                let answers = Part::ALL
+                    .map(|part| example.answer(part).map_or("-".to_string(), |v| v.to_string()))
+                    .join(", ");
+                rsx!{
+                    option {
+                        value: "{ix}",
+                        "Example {ix + 1} (answers: {answers})"
+                    }
+                }
+            })
+        }
+    }
+}
+
+#[inline_props]
+fn Running<'a>(cx: Scope<'a>, on_cancel: EventHandler<'a, MouseEvent>) -> Element<'a> {
+    render!{
+        div {
+            class: "flex items-center gap-2 pl-2",
+            div {
+                class: "w-4 h-4 border-2 border-blue-600 border-t-transparent rounded-full animate-spin",
+            }
+            span { "Solving..." }
+            button {
+                r#type: "button",
+                class: "px-2 border rounded-md hover:bg-gray-100",
+                onclick: move |event| on_cancel.call(event),
+                "Cancel"
+            }
+        }
+    }
+}
+
+/// Solved puzzle with the answer verdict and the duration in milliseconds
+type SolvedPuzzle = (PuzzleId, anyhow::Result<(Solution, Verdict, f64)>);
+
 #[inline_props]
 fn Answer<'a>(
-    cx: Scope,
-    answer: &'a Option<anyhow::Result<(String, f64)>>
-) -> Element {
+    cx: Scope<'a>,
+    answer: &'a Option<anyhow::Result<Vec<SolvedPuzzle>>>
+) -> Element<'a> {
     match answer {
-        Some(Ok((res, duration))) => render!{
-            p {
-                span { class: "pl-2", "Result: " }
-                span { "{res}" }
-            }
-            p {
-                span { class: "pl-2", "Duration: " }
-                span { "{duration}ms" }
+        // Results of several puzzles are shown side by side
+        Some(Ok(results)) => render!{
+            div {
+                class: "grid grid-flow-col auto-cols-fr gap-2",
+                results.iter().map(|(puzzle_id, outcome)| rsx!{
+                    div {
+                        p {
+                            class: "pl-2 font-bold",
+                            "{puzzle_id}"
+                        }
+                        PuzzleAnswer { outcome: outcome }
+                    }
+                })
             }
         },
         Some(Err(e)) => render!{
@@ -180,14 +369,119 @@ fn Answer<'a>(
         },
         None => None,
     }
+}
 
+#[inline_props]
+fn PuzzleAnswer<'a>(
+    cx: Scope<'a>,
+    outcome: &'a anyhow::Result<(Solution, Verdict, f64)>
+) -> Element<'a> {
+    match outcome {
+        Ok((solution, verdict, duration)) => render!{
+            p {
+                span { class: "pl-2", "Result: " }
+                span { "{solution.value}" }
+                match verdict {
+                    Verdict::Correct => rsx!{
+                        span { class: "pl-2 text-green-600", "✔ {verdict}" }
+                    },
+                    Verdict::Incorrect { .. } => rsx!{
+                        span { class: "pl-2 text-red-600", "✘ {verdict}" }
+                    },
+                    Verdict::Unknown => rsx!{
+                        span { class: "pl-2 text-gray-500", "({verdict})" }
+                    },
+                }
+            }
+            p {
+                span { class: "pl-2", "Duration: " }
+                span { "{duration}ms" }
+            }
+            solution.diagnostics.iter().map(|diagnostic| rsx!{
+                p {
+                    span { class: "pl-2", "{diagnostic.name}: " }
+                    span { "{diagnostic.value}" }
+                }
+            })
+            if let Some(visualization) = &solution.visualization {
+                rsx!{
+                    pre {
+                        class: "p-2 overflow-x-auto font-mono text-xs",
+                        "{visualization}"
+                    }
+                }
+            }
+        },
+        Err(e) => match e.downcast_ref::<ParseError>() {
+            Some(parse_error) => render!{
+                ParseErrorView { parse_error: parse_error }
+            },
+            None => render!{
+                p {
+                    span { class: "pl-2", "Error: " }
+                    span { "{e}" }
+                }
+            },
+        },
+    }
+}
+
+#[inline_props]
+fn ParseErrorView<'a>(cx: Scope<'a>, parse_error: &'a ParseError) -> Element<'a> {
+    let line = parse_error.line;
+    let marker = format!("{:>width$}", "^", width = parse_error.column);
+
+    render!{
+        p {
+            span { class: "pl-2", "Error: " }
+            span { "{parse_error.message}" }
+        }
+        p {
+            span { class: "pl-2", "Location: " }
+            button {
+                r#type: "button",
+                class: "text-blue-600 underline",
+                onclick: move |_| select_input_line(line),
+                "line {line}, column {parse_error.column}"
+            }
+        }
+        pre {
+            class: "p-2 overflow-x-auto font-mono text-xs",
+            "{parse_error.snippet}\n{marker}"
+        }
+    }
+}
+
+/// Selects the line of the input textarea, the line number is one-based
+fn select_input_line(line: usize) {
+    let textarea = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id("input"))
+        .and_then(|element| element.dyn_into::<HtmlTextAreaElement>().ok());
+    let Some(textarea) = textarea else {
+        return;
+    };
+
+    // Selection range is measured in UTF-16 code units
+    let value = textarea.value();
+    let line_lengths = value.split('\n')
+        .map(|l| l.encode_utf16().count())
+        .collect::<Vec<_>>();
+    let start = line_lengths.iter()
+        .take(line.saturating_sub(1))
+        .map(|len| len + 1)
+        .sum::<usize>();
+    let end = start + line_lengths.get(line.saturating_sub(1)).copied().unwrap_or(0);
+    textarea.focus().ok();
+    textarea.set_selection_range(start as u32, end as u32).ok();
 }
 
 #[inline_props]
 fn Puzzles<'a>(
     cx: Scope,
     cur_puzzle_id: String,
-    src: &'a UseState<&'static str>,
+    cur_day: &'a UseState<&'static Day>,
+    input: &'a UseState<String>,
 ) -> Element {
     render!{
         div {
@@ -198,24 +492,18 @@ fn Puzzles<'a>(
             }
             div {
                 class: "grid auto-cols-min gap-2 grid-cols-[repeat(auto-fill,_minmax(50px,_1fr))]",
-                DAYS.iter().enumerate().map(|(day_ord, day)| {
-                    let day_ord = day_ord as u32 + 1;
+                registry::days().iter().map(|day| {
                     rsx!{
                         div {
-                            PuzzleButton {
-                                day_ord: day_ord,
-                                puzzle_ord: 1,
-                                code: day.code,
-                                cur_puzzle_id: &cur_puzzle_id,
-                                src: src,
-                            }
-                            PuzzleButton {
-                                day_ord: day_ord,
-                                puzzle_ord: 2,
-                                code: day.code,
-                                cur_puzzle_id: &cur_puzzle_id,
-                                src: src,
-                            }
+                            Part::ALL.into_iter().map(|part| rsx!{
+                                PuzzleButton {
+                                    day: day,
+                                    part: part,
+                                    cur_puzzle_id: &cur_puzzle_id,
+                                    cur_day: cur_day,
+                                    input: input,
+                                }
+                            })
                         }
                     }
                 })
@@ -227,16 +515,20 @@ fn Puzzles<'a>(
 #[inline_props]
 fn PuzzleButton<'a>(
     cx: Scope,
-    day_ord: u32,
-    puzzle_ord: u32,
-    code: &'static str,
+    day: &'static Day,
+    part: Part,
     cur_puzzle_id: &'a str,
-    src: &'a UseState<&'static str>,
+    cur_day: &'a UseState<&'static Day>,
+    input: &'a UseState<String>,
 ) -> Element {
     let window = web_sys::window().expect("Window object");
     let location = window.location();
-    let puzzle_id = format!("{day_ord:0>2}-{puzzle_ord}");
+    let puzzle_id = day.puzzle_id(*part).to_string();
     let new_hash = format!("#{puzzle_id}");
+    let status_class = match day.status {
+        Status::Solved => "",
+        Status::Unfinished => "border-dashed text-gray-400",
+    };
 
     render!{
         div {
@@ -251,9 +543,11 @@ fn PuzzleButton<'a>(
             }
             label {
                 r#for: "puzzle-{puzzle_id}",
-                class: "block whitespace-nowrap p-2 border rounded-lg cursor-pointer hover:text-gray-600 hover:bg-gray-100 peer-checked:border-blue-600 peer-checked:text-blue-600",
+                class: "block whitespace-nowrap p-2 border rounded-lg cursor-pointer hover:text-gray-600 hover:bg-gray-100 peer-checked:border-blue-600 peer-checked:text-blue-600 {status_class}",
+                title: "{day.status}",
                 onclick: move |event| {
-                    src.set(code);
+                    cur_day.set(day);
+                    input.set(storage::load_input(day.ord).unwrap_or_default());
                     location.set_hash(&new_hash).ok();
                     event.stop_propagation();
                 },
@@ -264,55 +558,42 @@ fn PuzzleButton<'a>(
 }
 
 #[inline_props]
-fn Source(cx: Scope, code: String) -> Element {
-    // let code = cx.props.code.clone();
-    // log::info!("Got code: {}", &code);
-
-    let create_eval = use_eval(cx);
-    let hl_code = use_state(cx, String::new);
-
-    let future = use_future!(cx, |code| {
-        to_owned![create_eval];
-        let hl_code = hl_code.clone();
-        async move {
-            match highlight_code(&code, create_eval).await {
-                Ok(new_hl_code) => hl_code.set(new_hl_code),
-                Err(e) => log::error!("Error when highlighting code block: {e:?}"),
-            }
-        }
+fn Source<'a>(cx: Scope<'a>, day: &'a Day, part: Part) -> Element<'a> {
+    // Code of the other part and tests are folded by default
+    let only_part = use_state(cx, || true);
+    let show_tests = use_state(cx, || false);
+
+    let shown_part = only_part.then_some(*part);
+    let hl_code = use_memo(cx, (&day.ord, &shown_part, show_tests.get()), |(_, shown_part, show_tests)| {
+        highlight::highlight_rust(&day.source_map().render(shown_part, show_tests))
     });
-    future.value();
     render!{
+        div {
+            class: "flex gap-4 pb-2",
+            label {
+                input {
+                    r#type: "checkbox",
+                    class: "mr-1",
+                    checked: *only_part.get(),
+                    onclick: move |_| only_part.set(!only_part.get()),
+                }
+                "Only part {part}"
+            }
+            label {
+                input {
+                    r#type: "checkbox",
+                    class: "mr-1",
+                    checked: *show_tests.get(),
+                    onclick: move |_| show_tests.set(!show_tests.get()),
+                }
+                "Show tests"
+            }
+        }
         pre {
             code {
-                class: "language-rust",
+                class: "hljs language-rust",
                 dangerous_inner_html: "{hl_code}"
             }
         }
     }
 }
-
-async fn highlight_code(
-    code: &str,
-    create_eval: Rc<dyn Fn(&str) -> Result<UseEval, EvalError>>
-) -> anyhow::Result<String> {
-    // log::info!("Evaluating JS code...");
-    let eval = create_eval(
-        r#"
-        let code = await dioxus.recv();
-        let hlCode = hljs.highlight(code, {"language": "rust"}).value;
-        let hlCodeWithLines = hljs.lineNumbersValue(hlCode, {});
-        dioxus.send(hlCodeWithLines);
-        "#,
-    ).map_err(|e| anyhow::anyhow!("Cannot create eval object: {e:?}"))?;
-    eval.send(serde_json::Value::String(code.to_string()))
-        .map_err(|e| anyhow::anyhow!("Error communicating with JS: {e:?}"))?;
-
-    let eval_res = eval.recv().await
-        .map_err(|e| anyhow::anyhow!("Error communicating with JS: {e:?}"))?;
-    if let serde_json::Value::String(hl_code) = eval_res {
-        Ok(hl_code)
-    } else {
-        anyhow::bail!("Expected string after JS evaluation")
-    }
-}
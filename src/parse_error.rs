@@ -0,0 +1,116 @@
+use std::fmt;
+
+use anyhow::Context;
+
+use serde_json::json;
+
+/// Error that points to the place in the input where parsing failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// One-based line number
+    pub line: usize,
+    /// One-based column counted in characters
+    pub column: usize,
+    /// The whole offending line
+    pub snippet: String,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at line {}, column {}", self.message, self.line, self.column)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "line": self.line,
+            "column": self.column,
+            "snippet": self.snippet,
+            "message": self.message,
+        })
+    }
+
+    pub fn from_json(value: &serde_json::Value) -> anyhow::Result<Self> {
+        Ok(Self {
+            line: value["line"].as_u64().context("Expect line")? as usize,
+            column: value["column"].as_u64().context("Expect column")? as usize,
+            snippet: value["snippet"].as_str().context("Expect snippet")?.to_string(),
+            message: value["message"].as_str().context("Expect message")?.to_string(),
+        })
+    }
+}
+
+/// Line of the input that knows its number
+#[derive(Clone, Copy, Debug)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Makes an error pointing to the fragment that must be a slice of the line text
+    pub fn error(&self, fragment: &str, message: impl fmt::Display) -> ParseError {
+        let offset = (fragment.as_ptr() as usize)
+            .checked_sub(self.text.as_ptr() as usize)
+            .filter(|&offset| offset + fragment.len() <= self.text.len());
+        debug_assert!(offset.is_some(), "Fragment {fragment:?} is not a part of line {}", self.number);
+        let offset = offset.unwrap_or(0);
+        ParseError {
+            line: self.number,
+            column: self.text[..offset].chars().count() + 1,
+            snippet: self.text.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines()
+        .enumerate()
+        .map(|(ix, text)| Line { number: ix + 1, text })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_error() {
+        let lines = lines("Card 1: 41 48\nCard 2: 1ö 9x").collect::<Vec<_>>();
+        let line = lines[1];
+        let err = line.error(&line.text[12..], "Expect integer");
+        assert_eq!(
+            err,
+            ParseError {
+                line: 2,
+                column: 12,
+                snippet: "Card 2: 1ö 9x".to_string(),
+                message: "Expect integer".to_string(),
+            }
+        );
+        assert_eq!(err.to_string(), "Expect integer at line 2, column 12");
+    }
+
+    #[test]
+    #[should_panic(expected = "Fragment \"9x\" is not a part of line 2")]
+    fn test_line_error_with_foreign_fragment() {
+        let line = Line { number: 2, text: "Card 2: 1ö 9x" };
+        line.error(&String::from("9x"), "Expect integer");
+    }
+
+    #[test]
+    fn test_json() -> anyhow::Result<()> {
+        let err = ParseError {
+            line: 3,
+            column: 5,
+            snippet: "32T3X 765".to_string(),
+            message: "Unknown card 'X'".to_string(),
+        };
+        assert_eq!(ParseError::from_json(&err.to_json())?, err);
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Point {
+    pub const fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// Moves one step toward the direction, `None` when it would leave the non-negative quadrant
+    pub fn step(self, dir: Direction) -> Option<Self> {
+        self.offset(dir.offset())
+    }
+
+    pub fn offset(self, (d_row, d_col): (isize, isize)) -> Option<Self> {
+        Some(Self::new(
+            self.row.checked_add_signed(d_row)?,
+            self.col.checked_add_signed(d_col)?,
+        ))
+    }
+
+    pub fn manhattan_distance(self, other: Self) -> usize {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+    /// Row and column deltas of a single step
+    pub const fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+        }
+    }
+
+    pub const fn turn_left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    pub const fn turn_right(self) -> Self {
+        self.turn_left().reverse()
+    }
+
+    pub const fn reverse(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    pub const fn is_vertical(self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step() {
+        let p = Point::new(0, 2);
+        assert_eq!(p.step(Direction::Up), None);
+        assert_eq!(p.step(Direction::Down), Some(Point::new(1, 2)));
+        assert_eq!(p.step(Direction::Left), Some(Point::new(0, 1)));
+        assert_eq!(p.step(Direction::Right), Some(Point::new(0, 3)));
+        assert_eq!(Point::new(1, 0).step(Direction::Left), None);
+        assert_eq!(p.offset((2, -2)), Some(Point::new(2, 0)));
+    }
+
+    #[test]
+    fn test_turns() {
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.turn_right().turn_right(), dir.reverse());
+            assert_ne!(dir.turn_left().is_vertical(), dir.is_vertical());
+        }
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Right.turn_right(), Direction::Down);
+    }
+
+    #[test]
+    fn test_manhattan_distance() {
+        assert_eq!(Point::new(6, 1).manhattan_distance(Point::new(11, 5)), 9);
+        assert_eq!(Point::new(11, 5).manhattan_distance(Point::new(6, 1)), 9);
+        assert_eq!(Point::new(3, 3).manhattan_distance(Point::new(3, 3)), 0);
+    }
+}
@@ -0,0 +1,275 @@
+use std::any::Any;
+use std::fmt;
+use std::panic;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use anyhow::Context;
+
+use crate::solution::Value;
+use crate::source_map::SourceMap;
+use crate::{PuzzleResult, DAYS};
+
+pub type Solver = fn(&str) -> PuzzleResult;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn ord(&self) -> u32 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.ord())
+    }
+}
+
+impl FromStr for Part {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => anyhow::bail!("Part must be 1 or 2: {s}"),
+        }
+    }
+}
+
+/// Identifies a single puzzle, formatted as `DD-P`, for example `05-2`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PuzzleId {
+    pub day: u32,
+    pub part: Part,
+}
+
+impl PuzzleId {
+    pub fn new(day: u32, part: Part) -> Self {
+        Self { day, part }
+    }
+}
+
+impl fmt::Display for PuzzleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:0>2}-{}", self.day, self.part)
+    }
+}
+
+impl FromStr for PuzzleId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (day_str, part_str) = s.split_once('-')
+            .context("Expect '-' between day and part")?;
+        let day = day_str.parse()
+            .with_context(|| format!("Day must be an integer: {day_str}"))?;
+        let part = part_str.parse()?;
+        Ok(Self { day, part })
+    }
+}
+
+/// Example input from the puzzle description.
+/// An answer is `None` when the example is not meant for that part
+#[derive(Clone, Copy, Debug)]
+pub struct Example {
+    pub input: &'static str,
+    pub answer1: Option<i64>,
+    pub answer2: Option<i64>,
+}
+
+impl Example {
+    pub const fn new(input: &'static str, answer1: Option<i64>, answer2: Option<i64>) -> Self {
+        Self { input, answer1, answer2 }
+    }
+
+    pub fn answer(&self, part: Part) -> Option<Value> {
+        match part {
+            Part::One => self.answer1,
+            Part::Two => self.answer2,
+        }.map(Value::from)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Solved,
+    /// Solvers are stubs that can fail or panic
+    #[allow(dead_code)] // Set through `days!` only while a day is in progress
+    Unfinished,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Solved => write!(f, "solved"),
+            Status::Unfinished => write!(f, "unfinished"),
+        }
+    }
+}
+
+pub struct Day {
+    pub ord: u32,
+    pub code: &'static str,
+    pub solve1: Solver,
+    pub solve2: Solver,
+    pub examples: &'static [Example],
+    pub status: Status,
+    source_map: OnceLock<SourceMap<'static>>,
+}
+
+impl Day {
+    pub const fn new(
+        ord: u32,
+        code: &'static str,
+        solve1: Solver,
+        solve2: Solver,
+        examples: &'static [Example],
+    ) -> Self {
+        Self {
+            ord,
+            code,
+            solve1,
+            solve2,
+            examples,
+            status: Status::Solved,
+            source_map: OnceLock::new(),
+        }
+    }
+
+    #[allow(dead_code)] // Set through `days!` only while a day is in progress
+    pub const fn with_status(mut self, status: Status) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn solver(&self, part: Part) -> Solver {
+        match part {
+            Part::One => self.solve1,
+            Part::Two => self.solve2,
+        }
+    }
+
+    /// Runs the solver of the part turning a panic into an error.
+    /// In WASM panics abort, they are reported by the worker panic hook instead
+    pub fn solve(&self, part: Part, input: &str) -> PuzzleResult {
+        let solver = self.solver(part);
+        panic::catch_unwind(|| solver(input))
+            .unwrap_or_else(|payload| {
+                Err(anyhow::anyhow!("Solver panicked: {}", panic_message(payload.as_ref())))
+            })
+    }
+
+    pub fn puzzle_id(&self, part: Part) -> PuzzleId {
+        PuzzleId::new(self.ord, part)
+    }
+
+    /// Spans of the solvers, their helpers and tests inside the code.
+    /// The code is parsed on the first call
+    pub fn source_map(&self) -> &SourceMap<'static> {
+        self.source_map.get_or_init(|| SourceMap::parse(self.code))
+    }
+
+    /// Finds the example the input was taken from, trailing whitespaces are ignored
+    pub fn find_example(&self, input: &str) -> Option<&'static Example> {
+        self.examples.iter()
+            .find(|example| example.input.trim_end() == input.trim_end())
+    }
+}
+
+/// All registered days ordered by their ordinal
+pub fn days() -> &'static [Day] {
+    &DAYS
+}
+
+pub fn find_day(ord: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.ord == ord)
+}
+
+pub fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown reason"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_are_ordered() {
+        for (prev_day, day) in days().iter().zip(days().iter().skip(1)) {
+            assert!(prev_day.ord < day.ord, "{} goes before {}", prev_day.ord, day.ord);
+        }
+    }
+
+    #[test]
+    fn test_find_day() {
+        for day in days() {
+            assert_eq!(find_day(day.ord).map(|d| d.ord), Some(day.ord));
+        }
+        assert!(find_day(0).is_none());
+        assert!(find_day(26).is_none());
+    }
+
+    #[test]
+    fn test_source_map_is_built_once() {
+        let day = find_day(1).unwrap();
+        assert!(std::ptr::eq(day.source_map(), day.source_map()));
+    }
+
+    #[test]
+    fn test_solve() -> anyhow::Result<()> {
+        let day = find_day(6).unwrap();
+        assert_eq!(day.solve(Part::One, day.examples[0].input)?.value, 288.into());
+
+        fn panicking_solver(_input: &str) -> PuzzleResult {
+            panic!("Boom")
+        }
+        let day = Day::new(26, "", panicking_solver, panicking_solver, &[]);
+        assert_eq!(day.solve(Part::Two, "").unwrap_err().to_string(), "Solver panicked: Boom");
+        Ok(())
+    }
+
+    #[test]
+    fn test_examples() -> anyhow::Result<()> {
+        for day in days() {
+            if day.status == Status::Unfinished {
+                continue;
+            }
+            assert!(!day.examples.is_empty(), "Day {} has no examples", day.ord);
+            for example in day.examples {
+                for part in Part::ALL {
+                    if let Some(answer) = example.answer(part) {
+                        assert_eq!(day.solver(part)(example.input)?.value, answer);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_puzzle_id() -> anyhow::Result<()> {
+        let puzzle_id = "05-2".parse::<PuzzleId>()?;
+        assert_eq!(puzzle_id, PuzzleId::new(5, Part::Two));
+        assert_eq!(puzzle_id.to_string(), "05-2");
+        assert!("05-3".parse::<PuzzleId>().is_err());
+        assert!("05".parse::<PuzzleId>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Result of a search. Costs and paths are final only for visited states,
+/// other states were discovered but not expanded before the goal was found
+#[derive(Debug)]
+pub struct Search<S> {
+    states: Vec<S>,
+    state_ixs: HashMap<S, usize>,
+    costs: Vec<u64>,
+    predecessors: Vec<Option<usize>>,
+    visited: Vec<bool>,
+    goal: Option<usize>,
+}
+
+impl<S: Clone + Eq + Hash> Search<S> {
+    fn new() -> Self {
+        Self {
+            states: vec!(),
+            state_ixs: HashMap::new(),
+            costs: vec!(),
+            predecessors: vec!(),
+            visited: vec!(),
+            goal: None,
+        }
+    }
+
+    /// Records the state when it is new or reached cheaper than before,
+    /// a cheaper state has to be expanded again even if it was visited.
+    /// Returns the index of the state in the latter cases
+    fn discover(&mut self, state: S, cost: u64, predecessor: Option<usize>) -> Option<usize> {
+        match self.state_ixs.get(&state) {
+            Some(&ix) if self.costs[ix] <= cost => None,
+            Some(&ix) => {
+                self.costs[ix] = cost;
+                self.predecessors[ix] = predecessor;
+                self.visited[ix] = false;
+                Some(ix)
+            }
+            None => {
+                let ix = self.states.len();
+                self.state_ixs.insert(state.clone(), ix);
+                self.states.push(state);
+                self.costs.push(cost);
+                self.predecessors.push(predecessor);
+                self.visited.push(false);
+                Some(ix)
+            }
+        }
+    }
+
+    /// The first goal state that was reached
+    pub fn goal(&self) -> Option<&S> {
+        self.goal.map(|ix| &self.states[ix])
+    }
+
+    pub fn goal_cost(&self) -> Option<u64> {
+        self.goal.map(|ix| self.costs[ix])
+    }
+
+    /// States from a start state to the given one inclusive
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        let mut path = vec!();
+        let mut cur = self.state_ixs.get(state).copied();
+        while let Some(ix) = cur {
+            path.push(self.states[ix].clone());
+            cur = self.predecessors[ix];
+        }
+        path.reverse();
+        (!path.is_empty()).then_some(path)
+    }
+
+    /// Expanded states with their costs
+    pub fn visited(&self) -> impl Iterator<Item = (&S, u64)> {
+        self.states.iter()
+            .zip(&self.costs)
+            .zip(&self.visited)
+            .filter(|(_, &visited)| visited)
+            .map(|((state, &cost), _)| (state, cost))
+    }
+}
+
+/// Breadth-first search where every move costs 1
+pub fn bfs<S, N>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbors: impl FnMut(&S) -> N,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    N: IntoIterator<Item = S>,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        queue.extend(search.discover(start, 0, None));
+    }
+
+    while let Some(ix) = queue.pop_front() {
+        search.visited[ix] = true;
+        let state = search.states[ix].clone();
+        if is_goal(&state) {
+            search.goal = Some(ix);
+            break;
+        }
+        let next_cost = search.costs[ix] + 1;
+        for next in neighbors(&state) {
+            queue.extend(search.discover(next, next_cost, Some(ix)));
+        }
+    }
+
+    search
+}
+
+/// Finds the cheapest path from any of the start states to a goal state.
+/// `neighbors` returns the next states with the costs of moving to them.
+/// Same as `astar` without an estimate, also the reference `astar` is tested against
+#[allow(dead_code)] // No day needs it while day 17 has an estimate
+pub fn dijkstra<S, N>(
+    starts: impl IntoIterator<Item = S>,
+    neighbors: impl FnMut(&S) -> N,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    N: IntoIterator<Item = (S, u64)>,
+{
+    astar(starts, neighbors, |_| 0, is_goal)
+}
+
+/// Same as Dijkstra but the queue is ordered by the cost plus the estimate of the rest of the path.
+/// The estimate must never exceed the real cost. If it is not consistent, i.e. it can drop
+/// by more than the cost of a move, visited states are reopened when reached cheaper
+pub fn astar<S, N>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbors: impl FnMut(&S) -> N,
+    mut estimate: impl FnMut(&S) -> u64,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    N: IntoIterator<Item = (S, u64)>,
+{
+    let mut search = Search::new();
+    // States are kept in the search so the queue only needs their indexes
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        if let Some(ix) = search.discover(start, 0, None) {
+            queue.push(Reverse((estimate(&search.states[ix]), ix)));
+        }
+    }
+
+    while let Some(Reverse((priority, ix))) = queue.pop() {
+        let state = search.states[ix].clone();
+        if search.visited[ix] || priority > search.costs[ix] + estimate(&state) {
+            continue;
+        }
+        search.visited[ix] = true;
+        if is_goal(&state) {
+            search.goal = Some(ix);
+            break;
+        }
+        let cost = search.costs[ix];
+        for (next, step_cost) in neighbors(&state) {
+            let next_cost = cost + step_cost;
+            if let Some(next_ix) = search.discover(next, next_cost, Some(ix)) {
+                let priority = next_cost + estimate(&search.states[next_ix]);
+                queue.push(Reverse((priority, next_ix)));
+            }
+        }
+    }
+
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn weighted_graph() -> HashMap<u32, Vec<(u32, u64)>> {
+        // 0 -> 1 (7), 0 -> 2 (2), 2 -> 1 (3), 1 -> 3 (1), 2 -> 3 (10)
+        HashMap::from([
+            (0, vec!((1, 7), (2, 2))),
+            (1, vec!((3, 1))),
+            (2, vec!((1, 3), (3, 10))),
+        ])
+    }
+
+    #[test]
+    fn test_bfs() {
+        // Chain 0 -> 1 -> 2 -> 3 with a shortcut 0 -> 2
+        let edges = HashMap::from([(0, vec!(1, 2)), (1, vec!(2)), (2, vec!(3))]);
+        let neighbors = |n: &u32| edges.get(n).cloned().unwrap_or_default();
+
+        let search = bfs([0], neighbors, |&n| n == 3);
+        assert_eq!(search.goal(), Some(&3));
+        assert_eq!(search.goal_cost(), Some(2));
+        assert_eq!(search.path_to(&3), Some(vec!(0, 2, 3)));
+
+        let search = bfs([1], neighbors, |_| false);
+        assert_eq!(search.goal(), None);
+        assert_eq!(
+            search.visited().collect::<HashSet<_>>(),
+            HashSet::from([(&1, 0), (&2, 1), (&3, 2)])
+        );
+        assert_eq!(search.path_to(&0), None);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let edges = weighted_graph();
+        let neighbors = |n: &u32| edges.get(n).cloned().unwrap_or_default();
+
+        let search = dijkstra([0], neighbors, |&n| n == 3);
+        assert_eq!(search.goal_cost(), Some(6));
+        assert_eq!(search.path_to(&3), Some(vec!(0, 2, 1, 3)));
+        assert!(search.visited().any(|visited| visited == (&1, 5)));
+
+        assert_eq!(dijkstra([0], neighbors, |&n| n == 0).goal_cost(), Some(0));
+        assert_eq!(dijkstra([2, 1], neighbors, |&n| n == 3).goal_cost(), Some(1));
+        assert_eq!(dijkstra([3], neighbors, |&n| n == 0).goal_cost(), None);
+    }
+
+    #[test]
+    fn test_astar() {
+        let edges = weighted_graph();
+        let neighbors = |n: &u32| edges.get(n).cloned().unwrap_or_default();
+        let estimate = |n: &u32| if *n == 3 { 0 } else { 1 };
+
+        let search = astar([0], neighbors, estimate, |&n| n == 3);
+        assert_eq!(search.goal_cost(), Some(6));
+        assert_eq!(search.path_to(&3), Some(vec!(0, 2, 1, 3)));
+        for start in [0, 1, 2, 3] {
+            assert_eq!(
+                astar([start], neighbors, estimate, |&n| n == 3).goal_cost(),
+                dijkstra([start], neighbors, |&n| n == 3).goal_cost(),
+            );
+        }
+    }
+
+    #[test]
+    fn test_astar_with_inconsistent_estimate() {
+        // 0 -> 1 (1), 0 -> 2 (1), 1 -> 3 (1), 2 -> 3 (3), 3 -> 4 (5)
+        let edges = HashMap::from([
+            (0, vec!((1, 1), (2, 1))),
+            (1, vec!((3, 1))),
+            (2, vec!((3, 3))),
+            (3, vec!((4, 5))),
+        ]);
+        let neighbors = |n: &u32| edges.get(n).cloned().unwrap_or_default();
+        // Never exceeds the real cost 6 from node 1, yet delays it until 3 is visited via 2
+        let estimate = |n: &u32| if *n == 1 { 6 } else { 0 };
+
+        let search = astar([0], neighbors, estimate, |&n| n == 4);
+        assert_eq!(search.goal_cost(), dijkstra([0], neighbors, |&n| n == 4).goal_cost());
+        assert_eq!(search.goal_cost(), Some(7));
+        assert_eq!(search.path_to(&4), Some(vec!(0, 1, 3, 4)));
+    }
+}
@@ -0,0 +1,214 @@
+use std::fmt;
+
+use anyhow::Context;
+
+use num::BigInt;
+
+use serde_json::json;
+
+/// Puzzle answer value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Int(i64),
+    BigInt(BigInt),
+    Str(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(v) => write!(f, "{v}"),
+            Value::BigInt(v) => write!(f, "{v}"),
+            Value::Str(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+macro_rules! impl_value_from_int {
+    (lossless: $($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(v: $t) -> Self {
+                    Value::Int(v.into())
+                }
+            }
+        )*
+    };
+    (fallible: $($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(v: $t) -> Self {
+                    if let Ok(v) = i64::try_from(v) {
+                        Value::Int(v)
+                    } else {
+                        Value::BigInt(BigInt::from(v))
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_value_from_int!(lossless: i32, i64, u8, u32);
+impl_value_from_int!(fallible: i128, u64, u128, usize);
+
+impl From<BigInt> for Value {
+    fn from(v: BigInt) -> Self {
+        if let Ok(v) = i64::try_from(&v) {
+            Value::Int(v)
+        } else {
+            Value::BigInt(v)
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(v: String) -> Self {
+        Value::Str(v)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(v: &str) -> Self {
+        Value::Str(v.to_string())
+    }
+}
+
+/// Intermediate value that helps to understand how the answer was found
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub name: String,
+    pub value: String,
+}
+
+/// Result of a puzzle solver: the answer itself with optional extra output
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    pub value: Value,
+    pub diagnostics: Vec<Diagnostic>,
+    pub visualization: Option<String>,
+}
+
+impl Solution {
+    pub fn new(value: impl Into<Value>) -> Self {
+        Self {
+            value: value.into(),
+            diagnostics: vec!(),
+            visualization: None,
+        }
+    }
+
+    pub fn with_diagnostic(mut self, name: &str, value: impl fmt::Display) -> Self {
+        self.diagnostics.push(
+            Diagnostic { name: name.to_string(), value: value.to_string() }
+        );
+        self
+    }
+
+    pub fn with_visualization(mut self, visualization: String) -> Self {
+        self.visualization = Some(visualization);
+        self
+    }
+}
+
+impl Value {
+    /// Integers are encoded as strings as they may not fit into a JavaScript number
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::Int(v) => json!({ "int": v.to_string() }),
+            Value::BigInt(v) => json!({ "bigint": v.to_string() }),
+            Value::Str(v) => json!({ "str": v }),
+        }
+    }
+
+    pub fn from_json(value: &serde_json::Value) -> anyhow::Result<Self> {
+        let (kind, v) = value.as_object()
+            .and_then(|obj| obj.iter().next())
+            .context("Expect value object")?;
+        let v = v.as_str().context("Expect string value")?;
+        Ok(match kind.as_str() {
+            "int" => Value::Int(v.parse()?),
+            "bigint" => Value::BigInt(v.parse()?),
+            "str" => Value::Str(v.to_string()),
+            _ => anyhow::bail!("Unknown value type: {kind}"),
+        })
+    }
+}
+
+impl Solution {
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "value": self.value.to_json(),
+            "diagnostics": self.diagnostics.iter()
+                .map(|d| json!([d.name, d.value]))
+                .collect::<Vec<_>>(),
+            "visualization": self.visualization,
+        })
+    }
+
+    pub fn from_json(value: &serde_json::Value) -> anyhow::Result<Self> {
+        let mut solution = Solution::new(Value::from_json(&value["value"])?);
+        for diagnostic in value["diagnostics"].as_array().context("Expect diagnostics")? {
+            let name = diagnostic[0].as_str().context("Expect diagnostic name")?;
+            let value = diagnostic[1].as_str().context("Expect diagnostic value")?;
+            solution = solution.with_diagnostic(name, value);
+        }
+        if let Some(visualization) = value["visualization"].as_str() {
+            solution = solution.with_visualization(visualization.to_string());
+        }
+        Ok(solution)
+    }
+}
+
+impl<T: Into<Value>> From<T> for Solution {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_from_int() {
+        assert_eq!(Value::from(42u64), Value::Int(42));
+        assert_eq!(Value::from(-42), Value::Int(-42));
+        assert_eq!(
+            Value::from(u64::MAX),
+            Value::BigInt(BigInt::from(u64::MAX))
+        );
+        assert_eq!(Value::from(BigInt::from(42)), Value::Int(42));
+    }
+
+    #[test]
+    fn test_solution() {
+        let solution = Solution::from(42usize)
+            .with_diagnostic("cycle length", 7);
+        assert_eq!(solution.value, 42.into());
+        assert_eq!(solution.to_string(), "42");
+        assert_eq!(
+            solution.diagnostics,
+            vec!(Diagnostic { name: "cycle length".to_string(), value: "7".to_string() })
+        );
+        assert_eq!(solution.visualization, None);
+    }
+
+    #[test]
+    fn test_solution_json() -> anyhow::Result<()> {
+        let solution = Solution::from(u64::MAX)
+            .with_diagnostic("offset", 3)
+            .with_visualization("#.\n.#\n".to_string());
+        assert_eq!(Solution::from_json(&solution.to_json())?, solution);
+
+        let solution = Solution::from("TODO");
+        assert_eq!(Solution::from_json(&solution.to_json())?, solution);
+        Ok(())
+    }
+}
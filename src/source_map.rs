@@ -0,0 +1,350 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::lexer::{self, TokenKind};
+use crate::registry::Part;
+
+/// Keywords that define top level items
+const ITEM_KEYWORDS: &[&str] = &[
+    "fn", "struct", "enum", "union", "const", "static", "type", "trait", "mod", "macro_rules",
+    "impl", "use",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ItemKind {
+    /// Imports, examples and code used by both parts
+    Shared,
+    /// Solver of the part and helpers used only by it
+    Part(Part),
+    Tests,
+}
+
+#[derive(Clone, Debug)]
+pub struct Item {
+    pub kind: ItemKind,
+    /// Zero-based line numbers including leading comments and attributes
+    pub lines: Range<usize>,
+    /// Line with the item keyword, for example `pub fn solve_1(...) {`
+    header_line: usize,
+    /// Names an item can be referred by, an implementation is referred by its trait and type
+    ref_names: Vec<String>,
+    idents: HashSet<String>,
+}
+
+/// Splits the source code of a day into top level items and finds out which part uses them
+pub struct SourceMap<'a> {
+    code: &'a str,
+    items: Vec<Item>,
+}
+
+impl<'a> SourceMap<'a> {
+    pub fn parse(code: &'a str) -> Self {
+        let tokens = tokenize(code);
+        let lines = code.lines().collect::<Vec<_>>();
+
+        let mut items = vec!();
+        let mut item_start = 0;
+        let mut depth = 0_usize;
+        for (ix, (_, token)) in tokens.iter().enumerate() {
+            match token {
+                Token::Punct('{' | '(' | '[') => depth += 1,
+                Token::Punct('}' | ')' | ']') => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            let is_item_end = depth == 0 && match token {
+                Token::Punct(';') => true,
+                Token::Punct('}') => tokens.get(ix + 1).map(|(_, t)| t) != Some(&Token::Punct(';')),
+                _ => false,
+            };
+            if is_item_end {
+                let prev_end = items.last().map_or(0, |item: &Item| item.lines.end);
+                items.push(parse_item(&tokens[item_start..=ix], &lines, prev_end));
+                item_start = ix + 1;
+            }
+        }
+
+        let mut source_map = Self { code, items };
+        source_map.classify();
+        source_map
+    }
+
+    /// Returns the code where the items of the other part and optionally the tests are folded
+    /// into their headers. Items of both parts are shown when the part is `None`
+    pub fn render(&self, part: Option<Part>, show_tests: bool) -> String {
+        let lines = self.code.lines().collect::<Vec<_>>();
+        let mut rendered = String::new();
+        let mut line = 0;
+        for item in &self.items {
+            push_lines(&mut rendered, &lines[line..item.lines.start]);
+            let is_visible = match item.kind {
+                ItemKind::Shared => true,
+                ItemKind::Part(item_part) => part.is_none_or(|part| part == item_part),
+                ItemKind::Tests => show_tests,
+            };
+            if is_visible {
+                push_lines(&mut rendered, &lines[item.lines.clone()]);
+            } else {
+                push_lines(&mut rendered, &lines[item.lines.start..item.header_line]);
+                let header = lines[item.header_line].trim_end();
+                match header.strip_suffix('{') {
+                    Some(header) => writeln!(rendered, "{header}{{ ... }}"),
+                    None => writeln!(rendered, "{header} ..."),
+                }.ok();
+            }
+            line = item.lines.end;
+        }
+        push_lines(&mut rendered, &lines[line..]);
+        rendered
+    }
+
+    /// Items reachable from a solver belong to its part, items reachable from both are shared
+    fn classify(&mut self) {
+        let mut by_name = HashMap::<&str, Vec<usize>>::new();
+        for (ix, item) in self.items.iter().enumerate() {
+            for name in &item.ref_names {
+                by_name.entry(name.as_str()).or_default().push(ix);
+            }
+        }
+
+        let reachable = |solver: &str| {
+            let mut visited = HashSet::new();
+            let mut stack = by_name.get(solver).cloned().unwrap_or_default();
+            while let Some(ix) = stack.pop() {
+                if !visited.insert(ix) {
+                    continue;
+                }
+                for ident in &self.items[ix].idents {
+                    if let Some(ixs) = by_name.get(ident.as_str()) {
+                        stack.extend(ixs);
+                    }
+                }
+            }
+            visited
+        };
+        let reachable_1 = reachable("solve_1");
+        let reachable_2 = reachable("solve_2");
+
+        for (ix, item) in self.items.iter_mut().enumerate() {
+            if item.kind == ItemKind::Tests {
+                continue;
+            }
+            item.kind = match (reachable_1.contains(&ix), reachable_2.contains(&ix)) {
+                (true, false) => ItemKind::Part(Part::One),
+                (false, true) => ItemKind::Part(Part::Two),
+                _ => ItemKind::Shared,
+            };
+        }
+    }
+}
+
+fn push_lines(code: &mut String, lines: &[&str]) {
+    for line in lines {
+        code.push_str(line);
+        code.push('\n');
+    }
+}
+
+fn parse_item(tokens: &[(usize, Token)], lines: &[&str], prev_end: usize) -> Item {
+    let first_line = tokens.first().map_or(prev_end, |(line, _)| *line);
+    let last_line = tokens.last().map_or(prev_end, |(line, _)| *line);
+    // Doc comments are part of the item
+    let mut start = first_line;
+    while start > prev_end && lines[start - 1].trim_start().starts_with("//") {
+        start -= 1;
+    }
+
+    let mut depth = 0_usize;
+    let mut keyword = None;
+    let mut idents_before_keyword = vec!();
+    for (ix, (_, token)) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('{' | '(' | '[') => depth += 1,
+            Token::Punct('}' | ')' | ']') => depth = depth.saturating_sub(1),
+            Token::Ident(ident) if depth == 0 && ITEM_KEYWORDS.contains(&ident.as_str()) => {
+                let is_const_fn = ident == "const" && matches!(
+                    tokens.get(ix + 1), Some((_, Token::Ident(next))) if next == "fn"
+                );
+                if !is_const_fn {
+                    keyword = Some((ix, ident.as_str()));
+                    break;
+                }
+            }
+            Token::Ident(ident) => idents_before_keyword.push(ident.as_str()),
+            _ => {}
+        }
+    }
+
+    let header_line = keyword.map_or(first_line, |(ix, _)| tokens[ix].0);
+    let ref_names = match keyword {
+        Some((ix, "impl")) => impl_names(&tokens[ix + 1..]),
+        Some((_, "use")) | None => vec!(),
+        Some((ix, _)) => {
+            tokens[ix + 1..].iter()
+                .find_map(|(_, token)| match token {
+                    Token::Ident(ident) => Some(ident.clone()),
+                    _ => None,
+                })
+                .into_iter()
+                .collect()
+        }
+    };
+    let is_tests = matches!(keyword, Some((_, "mod")))
+        && idents_before_keyword.contains(&"cfg")
+        && idents_before_keyword.contains(&"test");
+
+    let idents = tokens.iter()
+        .filter_map(|(_, token)| match token {
+            Token::Ident(ident) => Some(ident.clone()),
+            _ => None,
+        })
+        .collect();
+
+    Item {
+        kind: if is_tests { ItemKind::Tests } else { ItemKind::Shared },
+        lines: start..last_line + 1,
+        header_line,
+        ref_names,
+        idents,
+    }
+}
+
+/// Trait and type names from the header of an implementation: `impl<T> Trait for Type<T> {`
+fn impl_names(tokens: &[(usize, Token)]) -> Vec<String> {
+    let mut names = vec!();
+    let mut last_ident = None;
+    let mut angle_depth = 0_usize;
+    for (_, token) in tokens {
+        match token {
+            Token::Punct('<') => angle_depth += 1,
+            Token::Punct('>') => angle_depth = angle_depth.saturating_sub(1),
+            Token::Punct('{') if angle_depth == 0 => break,
+            Token::Ident(ident) if angle_depth == 0 && ident == "for" => {
+                names.extend(last_ident.take());
+            }
+            Token::Ident(ident) if angle_depth == 0 && ident != "where" => {
+                last_ident = Some(ident.clone());
+            }
+            _ => {}
+        }
+    }
+    names.extend(last_ident);
+    names
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Punct(char),
+}
+
+/// Identifiers and punctuation with zero-based line numbers,
+/// comments, literals and lifetimes are skipped
+fn tokenize(code: &str) -> Vec<(usize, Token)> {
+    let mut tokens = vec!();
+    let mut line = 0;
+    for (kind, text) in lexer::tokenize(code) {
+        match kind {
+            TokenKind::Ident => tokens.push((line, Token::Ident(text.to_string()))),
+            TokenKind::Punct => tokens.extend(text.chars().map(|c| (line, Token::Punct(c)))),
+            _ => {}
+        }
+        line += text.matches('\n').count();
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use super::*;
+
+    const CODE: &str = indoc!{r#"
+        use std::str::FromStr;
+
+        pub fn solve_1(input: &str) -> crate::PuzzleResult {
+            Ok(parse(input)?.len().into())
+        }
+
+        pub fn solve_2(input: &str) -> crate::PuzzleResult {
+            let cards = parse(input)?;
+            Ok(Card::total(&cards).into())
+        }
+
+        /// Parses cards, one per line
+        fn parse(input: &str) -> anyhow::Result<Vec<Card>> {
+            let _braces_inside_literals = ("}", '\'','{');
+            input.lines().map(Card::from_str).collect()
+        }
+
+        struct Card(u32);
+
+        impl Card {
+            fn total(cards: &[Card]) -> u32 {
+                cards.iter().map(|c| c.0).sum()
+            }
+        }
+
+        impl FromStr for Card {
+            type Err = anyhow::Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok(Card(s.parse()?))
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+        }
+    "#};
+
+    #[test]
+    fn test_parse() {
+        let source_map = SourceMap::parse(CODE);
+        let items = source_map.items.iter()
+            .map(|item| (item.ref_names.last().map(String::as_str), item.kind, item.lines.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            vec!(
+                (None, ItemKind::Shared, 0..1),
+                (Some("solve_1"), ItemKind::Part(Part::One), 2..5),
+                (Some("solve_2"), ItemKind::Part(Part::Two), 6..10),
+                (Some("parse"), ItemKind::Shared, 11..16),
+                (Some("Card"), ItemKind::Shared, 17..18),
+                (Some("Card"), ItemKind::Shared, 19..24),
+                (Some("Card"), ItemKind::Shared, 25..32),
+                (Some("tests"), ItemKind::Tests, 33..37),
+            )
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let source_map = SourceMap::parse(CODE);
+        let rendered = source_map.render(Some(Part::One), false);
+        assert!(rendered.contains("    Ok(parse(input)?.len().into())\n"));
+        assert!(rendered.contains(
+            "\npub fn solve_2(input: &str) -> crate::PuzzleResult { ... }\n\n"
+        ));
+        assert!(rendered.contains("\n#[cfg(test)]\nmod tests { ... }\n"));
+        assert!(!rendered.contains("use super::*;"));
+
+        assert_eq!(source_map.render(None, true), CODE);
+    }
+
+    #[test]
+    fn test_days() {
+        for day in crate::registry::days() {
+            let source_map = day.source_map();
+            for (name, part) in [("solve_1", Part::One), ("solve_2", Part::Two)] {
+                let item = source_map.items.iter()
+                    .find(|item| item.ref_names == [name])
+                    .unwrap_or_else(|| panic!("Day {}: {name} not found", day.ord));
+                assert_eq!(item.kind, ItemKind::Part(part), "Day {}: {name}", day.ord);
+            }
+            assert_eq!(source_map.render(None, true), day.code, "Day {}", day.ord);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use web_sys::Storage;
+
+const INPUT_KEY_PREFIX: &str = "advent-2023/input/";
+
+fn local_storage() -> Option<Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+fn input_key(day_ord: u32) -> String {
+    format!("{INPUT_KEY_PREFIX}{day_ord:0>2}")
+}
+
+/// Returns the input that was pasted for the day last time
+pub fn load_input(day_ord: u32) -> Option<String> {
+    local_storage()?.get_item(&input_key(day_ord)).ok().flatten()
+}
+
+pub fn save_input(day_ord: u32, input: &str) {
+    let Some(storage) = local_storage() else {
+        return;
+    };
+    let key = input_key(day_ord);
+    let res = if input.is_empty() {
+        storage.remove_item(&key)
+    } else {
+        storage.set_item(&key, input)
+    };
+    if let Err(e) = res {
+        log::error!("Cannot save input for day {day_ord}: {e:?}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_key() {
+        assert_eq!(input_key(5), "advent-2023/input/05");
+        assert_eq!(input_key(16), "advent-2023/input/16");
+    }
+}
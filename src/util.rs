@@ -1,9 +1,19 @@
 use std::env::var;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
 
 use anyhow::Context;
 
 use dotenvy::dotenv;
 
+use crate::answers::{self, Verdict};
+use crate::input_cache::InputCache;
+use crate::registry::{Part, PuzzleId};
+use crate::solution::Solution;
+
 pub fn day_from_filename(filename: &str) -> Result<u8, anyhow::Error> {
     let day = filename
         .strip_suffix(".rs").context("Expected .rs file extension")?
@@ -14,7 +24,6 @@ pub fn day_from_filename(filename: &str) -> Result<u8, anyhow::Error> {
 }
 
 pub fn get_aoc_session() -> Result<String, anyhow::Error> {
-    dotenv()?;
     if let Ok(aoc_session) = var("AOC_SESSION") {
         Ok(aoc_session)
     } else {
@@ -22,20 +31,243 @@ pub fn get_aoc_session() -> Result<String, anyhow::Error> {
     }
 }
 
+pub trait InputProvider {
+    /// Returns `None` when the provider is not able to get the input
+    fn fetch(&self, day: u8) -> Result<Option<String>, anyhow::Error>;
+}
+
+/// Downloads user inputs from the Advent of Code site
+pub struct AocInputProvider {
+    base_url: String,
+    session: Option<String>,
+}
+
+impl AocInputProvider {
+    pub const DEFAULT_BASE_URL: &'static str = "https://adventofcode.com/2023";
+
+    pub fn new(base_url: &str, session: Option<String>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+        }
+    }
+
+    /// Uses `AOC_BASE_URL` and `AOC_SESSION` environment variables
+    pub fn from_env() -> Self {
+        let base_url = var("AOC_BASE_URL")
+            .unwrap_or_else(|_| Self::DEFAULT_BASE_URL.to_string());
+        Self::new(&base_url, get_aoc_session().ok())
+    }
+}
+
+impl InputProvider for AocInputProvider {
+    fn fetch(&self, day: u8) -> Result<Option<String>, anyhow::Error> {
+        let aoc_session = if let Some(sess) = &self.session {
+            sess
+        } else {
+            return Ok(None);
+        };
+
+        let client = reqwest::blocking::Client::new();
+        let input = client.get(format!("{}/day/{day}/input", self.base_url))
+            .header(reqwest::header::COOKIE, format!("session={aoc_session}"))
+            .send()?
+            .error_for_status()?
+            .text()?;
+        if input.is_empty() {
+            anyhow::bail!("Empty user input");
+        }
+        Ok(Some(input))
+    }
+}
+
+/// Reads inputs from the local cache and stores there inputs fetched by the inner provider
+pub struct CachedInputProvider<P> {
+    cache: InputCache,
+    inner: P,
+}
+
+impl<P: InputProvider> CachedInputProvider<P> {
+    pub fn new(cache: InputCache, inner: P) -> Self {
+        Self { cache, inner }
+    }
+}
+
+impl<P: InputProvider> InputProvider for CachedInputProvider<P> {
+    fn fetch(&self, day: u8) -> Result<Option<String>, anyhow::Error> {
+        if let Some(input) = self.cache.read(day.into())? {
+            return Ok(Some(input));
+        }
+
+        let input = self.inner.fetch(day)?;
+        if let Some(input) = &input {
+            self.cache.write(day.into(), input)?;
+        }
+        Ok(input)
+    }
+}
+
+/// Returns user input from the local cache downloading it when it is missing
 pub fn fetch_user_input(day: u8) -> Result<Option<String>, anyhow::Error> {
-    let aoc_session = if let Ok(sess) = get_aoc_session() {
-        sess
-    } else {
-        return Ok(None);
-    };
-
-    let client = reqwest::blocking::Client::new();
-    let input = client.get(format!("https://adventofcode.com/2023/day/{day}/input"))
-        .header(reqwest::header::COOKIE, format!("session={aoc_session}"))
-        .send()?
-        .text()?;
-    if input.is_empty() {
-        anyhow::bail!("Empty user input");
-    }
-    Ok(Some(input))
+    dotenv().ok();
+
+    CachedInputProvider::new(InputCache::from_env(), AocInputProvider::from_env())
+        .fetch(day)
+}
+
+pub fn check_answer(day: u8, part: Part, solution: &Solution) -> Result<(), anyhow::Error> {
+    let puzzle_id = PuzzleId::new(day.into(), part);
+    match answers::verify(puzzle_id, &solution.value) {
+        Verdict::Correct => {
+            log::warn!("{puzzle_id}: {solution} (correct)");
+        }
+        Verdict::Unknown => {
+            log::warn!("{puzzle_id}: {solution} (no expected answer)");
+        }
+        Verdict::Incorrect { expected } => {
+            anyhow::bail!("{puzzle_id}: expected {expected} but got {solution}");
+        }
+    }
+    Ok(())
+}
+
+/// Minimal HTTP server that answers every request with the same response
+pub struct MockServer {
+    pub base_url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockServer {
+    pub fn start(status: u16, body: &str) -> Result<Self, anyhow::Error> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+        let requests = Arc::new(Mutex::new(vec!()));
+
+        let response = format!(
+            "HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        let received_requests = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let mut reader = BufReader::new(&mut stream);
+                let mut request = String::new();
+                loop {
+                    let mut line = String::new();
+                    match reader.read_line(&mut line) {
+                        Ok(0) | Err(_) => break,
+                        Ok(_) if line == "\r\n" => break,
+                        Ok(_) => request.push_str(&line),
+                    }
+                }
+                received_requests.lock().unwrap().push(request);
+                stream.write_all(response.as_bytes()).ok();
+            }
+        });
+
+        Ok(Self { base_url, requests })
+    }
+
+    /// Heads of the received requests: request lines with headers
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Directory for the files of a test, it is removed when dropped even if the test fails
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir()
+            .join(format!("advent-2023-{name}-{}", std::process::id()));
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        // The directory is not created when the test fails early
+        std::fs::remove_dir_all(&self.path).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fetch_user_input() -> Result<(), anyhow::Error> {
+        let server = MockServer::start(200, "1abc2\n")?;
+        let provider = AocInputProvider::new(&server.base_url, Some("secret".to_string()));
+
+        assert_eq!(provider.fetch(1)?, Some("1abc2\n".to_string()));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        let request = requests[0].to_lowercase();
+        assert!(request.starts_with("get /day/1/input http/1.1\r\n"), "{request}");
+        assert!(request.contains("cookie: session=secret\r\n"), "{request}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_user_input_without_session() -> Result<(), anyhow::Error> {
+        let server = MockServer::start(200, "1abc2\n")?;
+        let provider = AocInputProvider::new(&server.base_url, None);
+
+        assert_eq!(provider.fetch(1)?, None);
+        assert!(server.requests().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_empty_user_input() -> Result<(), anyhow::Error> {
+        let server = MockServer::start(200, "")?;
+        let provider = AocInputProvider::new(&server.base_url, Some("secret".to_string()));
+
+        let err = provider.fetch(1).unwrap_err();
+        assert_eq!(err.to_string(), "Empty user input");
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_user_input_http_error() -> Result<(), anyhow::Error> {
+        let server = MockServer::start(400, "Puzzle inputs differ by user.")?;
+        let provider = AocInputProvider::new(&server.base_url, Some("expired".to_string()));
+
+        assert!(provider.fetch(1).is_err());
+
+        let server = MockServer::start(404, "Not found")?;
+        let provider = AocInputProvider::new(&server.base_url, Some("secret".to_string()));
+
+        assert!(provider.fetch(26).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cached_input_provider() -> Result<(), anyhow::Error> {
+        let server = MockServer::start(200, "1abc2\n")?;
+        let dir = TempDir::new("cached-input-provider");
+        let cache = InputCache::new(dir.path());
+        let provider = CachedInputProvider::new(
+            cache.clone(),
+            AocInputProvider::new(&server.base_url, Some("secret".to_string())),
+        );
+
+        assert_eq!(provider.fetch(1)?, Some("1abc2\n".to_string()));
+        assert_eq!(provider.fetch(1)?, Some("1abc2\n".to_string()));
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(cache.read(1)?, Some("1abc2\n".to_string()));
+        Ok(())
+    }
 }
@@ -0,0 +1,332 @@
+use std::cell::Cell;
+use std::panic::{self, PanicHookInfo};
+use std::rc::Rc;
+use std::sync::Once;
+use std::time::Duration;
+
+use anyhow::Context;
+
+use serde_json::json;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use web_sys::{
+    DedicatedWorkerGlobalScope, ErrorEvent, MessageEvent, Worker, WorkerGlobalScope, WorkerOptions,
+    WorkerType,
+};
+
+use crate::parse_error::ParseError;
+use crate::registry::{self, PuzzleId};
+use crate::solution::Solution;
+
+/// Script that loads the application module and calls [solve_puzzles], see `public/worker.js`.
+/// The path must include `base_path` from `Dioxus.toml`
+const WORKER_URL: &str = "/advent-2023/worker.js";
+
+/// Result of a single puzzle: solution with the duration in milliseconds
+pub type PuzzleOutcome = anyhow::Result<(Solution, f64)>;
+
+static PANIC_HOOK: Once = Once::new();
+
+/// Entry point for the web worker. Both request and response are JSON strings
+#[wasm_bindgen]
+pub fn solve_puzzles(request: &str) -> String {
+    PANIC_HOOK.call_once(|| panic::set_hook(Box::new(report_panic)));
+
+    let response = match handle_request(request) {
+        Ok(results) => {
+            let results = results.into_iter()
+                .map(|(puzzle_id, outcome)| match outcome {
+                    Ok((solution, duration)) => json!({
+                        "puzzle": puzzle_id.to_string(),
+                        "solution": solution.to_json(),
+                        "duration": duration,
+                    }),
+                    Err(e) => {
+                        let mut result = json!({
+                            "puzzle": puzzle_id.to_string(),
+                            "error": format!("{e:#}"),
+                        });
+                        // Keeps the location so the page can point to the failed line
+                        if let Some(parse_error) = e.downcast_ref::<ParseError>() {
+                            result["parse_error"] = parse_error.to_json();
+                        }
+                        result
+                    }
+                })
+                .collect::<Vec<_>>();
+            json!({ "results": results })
+        }
+        Err(e) => json!({ "error": format!("{e:#}") }),
+    };
+    response.to_string()
+}
+
+/// Panics abort in WASM so the error is sent before the module dies.
+/// A fatal response makes the page restart the worker
+fn report_panic(info: &PanicHookInfo) {
+    let response = json!({
+        "error": format!("Solver panicked: {}", registry::panic_message(info.payload())),
+        "fatal": true,
+    });
+    if let Ok(scope) = js_sys::global().dyn_into::<DedicatedWorkerGlobalScope>() {
+        scope.post_message(&JsValue::from_str(&response.to_string())).ok();
+    }
+}
+
+/// Runs all requested puzzles on the same input.
+/// A failed puzzle does not prevent the others from being solved
+fn handle_request(request: &str) -> anyhow::Result<Vec<(PuzzleId, PuzzleOutcome)>> {
+    let request = serde_json::from_str::<serde_json::Value>(request)?;
+    let puzzle_ids = request["puzzles"].as_array()
+        .context("Expect puzzles")?
+        .iter()
+        .map(|puzzle_id| puzzle_id.as_str().context("Expect puzzle")?.parse::<PuzzleId>())
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let input = request["input"].as_str().context("Expect input")?;
+
+    let perf = js_sys::global()
+        .dyn_into::<WorkerGlobalScope>()
+        .ok()
+        .and_then(|scope| scope.performance())
+        .context("Performance")?;
+    let results = puzzle_ids.into_iter()
+        .map(|puzzle_id| {
+            let outcome = registry::find_day(puzzle_id.day)
+                .context("Invalid task")
+                .and_then(|day| {
+                    let start = perf.now();
+                    let solution = day.solve(puzzle_id.part, input)?;
+                    let end = perf.now();
+                    Ok((solution, end - start))
+                });
+            (puzzle_id, outcome)
+        })
+        .collect();
+    Ok(results)
+}
+
+fn parse_response(response: &serde_json::Value) -> anyhow::Result<Vec<(PuzzleId, PuzzleOutcome)>> {
+    if let Some(error) = response["error"].as_str() {
+        anyhow::bail!("{error}");
+    }
+    response["results"].as_array()
+        .context("Expect results")?
+        .iter()
+        .map(|result| {
+            let puzzle_id = result["puzzle"].as_str()
+                .context("Expect puzzle")?
+                .parse::<PuzzleId>()?;
+            if result["parse_error"].is_object() {
+                let parse_error = ParseError::from_json(&result["parse_error"])?;
+                return Ok((puzzle_id, Err(parse_error.into())));
+            }
+            if let Some(error) = result["error"].as_str() {
+                return Ok((puzzle_id, Err(anyhow::anyhow!("{error}"))));
+            }
+            let solution = Solution::from_json(&result["solution"])?;
+            let duration = result["duration"].as_f64().context("Expect duration")?;
+            Ok((puzzle_id, Ok((solution, duration))))
+        })
+        .collect()
+}
+
+fn js_error(e: JsValue) -> anyhow::Error {
+    anyhow::anyhow!("{e:?}")
+}
+
+type ResultCallback = Rc<dyn Fn(anyhow::Result<Vec<(PuzzleId, PuzzleOutcome)>>)>;
+
+/// Runs solvers in a web worker so long computations do not block the page
+pub struct SolverWorker {
+    worker: Worker,
+    timeout: Duration,
+    timeout_handle: Rc<Cell<Option<i32>>>,
+    terminated: Rc<Cell<bool>>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_error: Closure<dyn FnMut(ErrorEvent)>,
+    on_timeout: Closure<dyn FnMut()>,
+}
+
+impl SolverWorker {
+    pub fn spawn(
+        timeout: Duration,
+        on_result: impl Fn(anyhow::Result<Vec<(PuzzleId, PuzzleOutcome)>>) + 'static,
+    ) -> anyhow::Result<Self> {
+        let mut options = WorkerOptions::new();
+        options.type_(WorkerType::Module);
+        let worker = Worker::new_with_options(WORKER_URL, &options).map_err(js_error)?;
+
+        let on_result: ResultCallback = Rc::new(on_result);
+        let timeout_handle = Rc::new(Cell::new(None));
+        let terminated = Rc::new(Cell::new(false));
+
+        let on_message = {
+            let worker = worker.clone();
+            let on_result = on_result.clone();
+            let timeout_handle = timeout_handle.clone();
+            let terminated = terminated.clone();
+            Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                // Only the first error is reported when the worker dies
+                if terminated.get() {
+                    return;
+                }
+                clear_timeout(&timeout_handle);
+                let res = event.data().as_string()
+                    .context("Expect string response")
+                    .and_then(|response| Ok(serde_json::from_str::<serde_json::Value>(&response)?))
+                    .and_then(|response| {
+                        if response["fatal"].as_bool() == Some(true) {
+                            // The module instance cannot be reused after a panic
+                            worker.terminate();
+                            terminated.set(true);
+                        }
+                        parse_response(&response)
+                    });
+                on_result(res);
+            })
+        };
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+        let on_error = {
+            let worker = worker.clone();
+            let on_result = on_result.clone();
+            let timeout_handle = timeout_handle.clone();
+            let terminated = terminated.clone();
+            Closure::<dyn FnMut(ErrorEvent)>::new(move |event: ErrorEvent| {
+                event.prevent_default();
+                clear_timeout(&timeout_handle);
+                // The module instance cannot be reused after a failure
+                worker.terminate();
+                terminated.set(true);
+                on_result(Err(anyhow::anyhow!("Solver failed: {}", event.message())));
+            })
+        };
+        worker.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+        let on_timeout = {
+            let worker = worker.clone();
+            let timeout_handle = timeout_handle.clone();
+            let terminated = terminated.clone();
+            Closure::<dyn FnMut()>::new(move || {
+                timeout_handle.set(None);
+                worker.terminate();
+                terminated.set(true);
+                on_result(Err(anyhow::anyhow!("Solver timed out after {timeout:?}")));
+            })
+        };
+
+        Ok(Self {
+            worker,
+            timeout,
+            timeout_handle,
+            terminated,
+            _on_message: on_message,
+            _on_error: on_error,
+            on_timeout,
+        })
+    }
+
+    pub fn is_terminated(&self) -> bool {
+        self.terminated.get()
+    }
+
+    /// Solves the puzzles one by one, results for all of them are passed to a single callback
+    pub fn solve(&self, puzzle_ids: &[PuzzleId], input: &str) -> anyhow::Result<()> {
+        if self.is_terminated() {
+            anyhow::bail!("Solver worker is terminated");
+        }
+
+        let puzzle_ids = puzzle_ids.iter()
+            .map(PuzzleId::to_string)
+            .collect::<Vec<_>>();
+        let request = json!({ "puzzles": puzzle_ids, "input": input });
+        self.worker.post_message(&JsValue::from_str(&request.to_string()))
+            .map_err(js_error)?;
+
+        let window = web_sys::window().context("Window object")?;
+        let handle = window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                self.on_timeout.as_ref().unchecked_ref(),
+                self.timeout.as_millis() as i32,
+            )
+            .map_err(js_error)?;
+        self.timeout_handle.set(Some(handle));
+        Ok(())
+    }
+
+    pub fn cancel(&self) {
+        clear_timeout(&self.timeout_handle);
+        self.worker.terminate();
+        self.terminated.set(true);
+    }
+}
+
+impl Drop for SolverWorker {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+fn clear_timeout(timeout_handle: &Cell<Option<i32>>) {
+    if let (Some(handle), Some(window)) = (timeout_handle.take(), web_sys::window()) {
+        window.clear_timeout_with_handle(handle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() -> anyhow::Result<()> {
+        let response = json!({
+            "results": [
+                {
+                    "puzzle": "06-1",
+                    "solution": Solution::from(288).to_json(),
+                    "duration": 1.5,
+                },
+                {
+                    "puzzle": "06-2",
+                    "error": "Expect integer",
+                },
+                {
+                    "puzzle": "07-1",
+                    "error": "Unknown card 'X' at line 2, column 4",
+                    "parse_error": {
+                        "line": 2,
+                        "column": 4,
+                        "snippet": "T55X5 684",
+                        "message": "Unknown card 'X'",
+                    },
+                },
+            ],
+        });
+        let results = parse_response(&response)?;
+        assert_eq!(results.len(), 3);
+
+        let (puzzle_id, outcome) = &results[0];
+        assert_eq!(puzzle_id.to_string(), "06-1");
+        let (solution, duration) = outcome.as_ref().unwrap();
+        assert_eq!(solution.value, 288.into());
+        assert_eq!(*duration, 1.5);
+
+        let (puzzle_id, outcome) = &results[1];
+        assert_eq!(puzzle_id.to_string(), "06-2");
+        assert_eq!(outcome.as_ref().unwrap_err().to_string(), "Expect integer");
+
+        let (_, outcome) = &results[2];
+        let err = outcome.as_ref().unwrap_err();
+        assert_eq!(err.to_string(), "Unknown card 'X' at line 2, column 4");
+        assert_eq!(err.downcast_ref::<ParseError>().map(|e| e.line), Some(2));
+
+        let response = json!({ "error": "Solver panicked: Boom", "fatal": true });
+        assert_eq!(
+            parse_response(&response).unwrap_err().to_string(),
+            "Solver panicked: Boom"
+        );
+        Ok(())
+    }
+}
@@ -5,10 +5,6 @@ module.exports = {
     "./src/**/*.{rs,html,css}",
     "./dist/**/*.html",
   ],
-  safelist: [
-    "hljs-ln-numbers",
-    "hljs-ln-code",
-  ],
   theme: {
     container: {
       center: true,